@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TYPE user_role AS ENUM ('Employee', 'Manager', 'Admin');
+
+ALTER TABLE users ALTER COLUMN role DROP DEFAULT;
+ALTER TABLE users ALTER COLUMN role TYPE user_role USING role::user_role;
+ALTER TABLE users ALTER COLUMN role SET DEFAULT 'Employee';
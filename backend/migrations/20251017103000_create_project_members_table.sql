@@ -0,0 +1,17 @@
+-- Add migration script here
+CREATE TYPE project_role AS ENUM ('Viewer', 'Editor', 'Owner');
+
+CREATE TABLE project_members (
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role project_role NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (project_id, user_id)
+);
+
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
+
+-- Existing project owners become owner members of their projects.
+INSERT INTO project_members (project_id, user_id, role)
+SELECT id, owner_id, 'Owner' FROM projects;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE projects
+ADD COLUMN archived_at TIMESTAMPTZ;
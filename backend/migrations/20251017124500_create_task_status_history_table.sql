@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE task_status_history (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    from_status task_status NOT NULL,
+    to_status task_status NOT NULL,
+    changed_by_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    comment TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_task_status_history_task_id ON task_status_history(task_id);
@@ -0,0 +1,25 @@
+-- Add migration script here
+ALTER TABLE workflow_templates
+ADD COLUMN version INT NOT NULL DEFAULT 1;
+
+CREATE TABLE workflow_instances (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    template_id UUID NOT NULL REFERENCES workflow_templates(id) ON DELETE CASCADE,
+    template_version INT NOT NULL,
+    applied_by_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (project_id, template_id)
+);
+
+CREATE TABLE workflow_instance_tasks (
+    instance_id UUID NOT NULL REFERENCES workflow_instances(id) ON DELETE CASCADE,
+    step_id UUID NOT NULL REFERENCES workflow_steps(id) ON DELETE CASCADE,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (instance_id, step_id)
+);
+
+CREATE INDEX idx_workflow_instance_tasks_task_id ON workflow_instance_tasks(task_id);
@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE TABLE project_summaries (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    summary TEXT NOT NULL,
+    -- Hash of the project's tasks and RACI rows the summary was written from.
+    tasks_fingerprint TEXT NOT NULL,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
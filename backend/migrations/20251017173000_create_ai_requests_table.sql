@@ -0,0 +1,27 @@
+-- Add migration script here
+-- One row per call to the AI provider, including repair attempts.
+CREATE TABLE ai_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    feature VARCHAR(100) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    prompt_template_id UUID REFERENCES prompt_templates(id),
+    -- NULL when the provider does not report usage.
+    prompt_tokens INT,
+    completion_tokens INT,
+    latency_ms INT NOT NULL,
+    -- NULL for successful calls.
+    error_code VARCHAR(50),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_ai_requests_created_at ON ai_requests(created_at);
+CREATE INDEX idx_ai_requests_user_id_created_at ON ai_requests(user_id, created_at);
+
+-- Per-user overrides of the default monthly token quota (AI_MONTHLY_TOKEN_QUOTA).
+CREATE TABLE ai_quotas (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- NULL means unlimited.
+    monthly_tokens BIGINT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Each sort option of a paginated list is backed by an (expression, id) index matching
+-- its ORDER BY, so keyset pages are index range scans.
+CREATE INDEX idx_projects_name_id ON projects(name, id);
+CREATE INDEX idx_projects_created_at_id ON projects(created_at, id);
+CREATE INDEX idx_projects_deadline_id ON projects(COALESCE(deadline, 'infinity'::timestamptz), id);
+
+CREATE INDEX idx_tasks_project_id_created_at_id ON tasks(project_id, created_at, id);
+CREATE INDEX idx_tasks_project_id_title_id ON tasks(project_id, title, id);
+CREATE INDEX idx_tasks_project_id_deadline_id ON tasks(project_id, COALESCE(deadline, 'infinity'::timestamptz), id);
+CREATE INDEX idx_tasks_assigned_to_id ON tasks(assigned_to_id);
+
+CREATE INDEX idx_users_email_id ON users(email, id);
+-- Supports the email prefix filter (LIKE 'prefix%').
+CREATE INDEX idx_users_email_pattern ON users(email varchar_pattern_ops);
+CREATE INDEX idx_users_created_at_id ON users(created_at, id);
+
+CREATE INDEX idx_workflow_templates_name_id ON workflow_templates(name, id);
+CREATE INDEX idx_workflow_templates_created_at_id ON workflow_templates(created_at, id);
@@ -0,0 +1,11 @@
+-- Add migration script here
+-- The Jira side of each linked task as last seen by the sync. A pulled status is only
+-- applied when it differs from `jira_status`, so local changes are not overwritten
+-- by a poll that ran before they reached Jira.
+CREATE TABLE task_jira_sync (
+    task_id UUID PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+    issue_key VARCHAR(100) NOT NULL,
+    jira_status VARCHAR(255),
+    synced_at TIMESTAMPTZ,
+    last_error TEXT
+);
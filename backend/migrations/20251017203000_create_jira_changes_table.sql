@@ -0,0 +1,17 @@
+-- Add migration script here
+-- Changes made in Jira and received through the webhook, one row per task and field.
+-- Rows with an error were not applied.
+CREATE TABLE jira_changes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    issue_key VARCHAR(100) NOT NULL,
+    field VARCHAR(50) NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    -- The Jira user who made the change, as named by Jira.
+    jira_user VARCHAR(255),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_jira_changes_task_id ON jira_changes(task_id);
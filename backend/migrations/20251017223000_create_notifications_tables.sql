@@ -0,0 +1,31 @@
+-- Add migration script here
+CREATE TYPE notification_kind AS ENUM ('Assigned', 'StatusChanged');
+
+-- In-app notifications. Assignments carry the RACI role given, status changes the
+-- statuses the task went from and to.
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Who made the change, if it was made in Clarika by a known user.
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    role raci_role,
+    from_status task_status,
+    to_status task_status,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notifications_user_created_at ON notifications(user_id, created_at, id);
+CREATE INDEX idx_notifications_user_unread ON notifications(user_id, created_at, id)
+WHERE read_at IS NULL;
+CREATE INDEX idx_notifications_task_id ON notifications(task_id);
+
+-- Users without a row get every notification.
+CREATE TABLE notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    assignments BOOLEAN NOT NULL DEFAULT TRUE,
+    status_changes BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Add migration script here
+-- Deleting a task created from a workflow step keeps the step's link, with no task, so
+-- syncing the template does not bring the deleted task back.
+ALTER TABLE workflow_instance_tasks ALTER COLUMN task_id DROP NOT NULL;
+ALTER TABLE workflow_instance_tasks
+    DROP CONSTRAINT workflow_instance_tasks_task_id_fkey,
+    ADD CONSTRAINT workflow_instance_tasks_task_id_fkey
+        FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL;
@@ -0,0 +1,8 @@
+-- Add migration script here
+-- The planning hints of AI scoping for the tasks created from it.
+CREATE TABLE ai_scoped_tasks (
+    task_id UUID PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+    estimate TEXT,
+    suggested_role TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- The prompt version that scoped each task, like other AI results.
+ALTER TABLE ai_scoped_tasks ADD COLUMN prompt_template_id UUID REFERENCES prompt_templates(id);
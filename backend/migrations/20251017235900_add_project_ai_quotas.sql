@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Team quotas: AI calls made for a project also count against that project's
+-- monthly quota, when one is set.
+ALTER TABLE ai_requests ADD COLUMN project_id UUID REFERENCES projects(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_ai_requests_project_id_created_at ON ai_requests(project_id, created_at);
+
+-- Projects without a row here have no project-wide limit.
+CREATE TABLE ai_project_quotas (
+    project_id UUID PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    monthly_tokens BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,127 @@
+use super::error::AiError;
+use reqwest::{Client, RequestBuilder, Response, StatusCode, header::RETRY_AFTER};
+use std::{env, sync::Arc, time::Duration};
+use tokio::{sync::Semaphore, time::sleep};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+// The HTTP client shared by all providers. It bounds every request with a
+// timeout, caps how many run at once and retries transient failures.
+#[derive(Clone)]
+pub struct AiClient {
+    http: Client,
+    in_flight: Arc<Semaphore>,
+    max_retries: u32,
+    base_backoff: Duration,
+}
+
+impl AiClient {
+    // Reads AI_TIMEOUT_SECS, AI_MAX_RETRIES, AI_RETRY_BASE_MS and AI_MAX_CONCURRENT.
+    pub fn from_env() -> Result<Self, AiError> {
+        let timeout = Duration::from_secs(env_number("AI_TIMEOUT_SECS", 60)?);
+        let http = Client::builder()
+            .timeout(timeout)
+            .connect_timeout(Duration::from_secs(10))
+            .build()?;
+
+        // With no permits every call would wait forever.
+        let max_concurrent = env_number("AI_MAX_CONCURRENT", 4)?;
+        if max_concurrent == 0 {
+            return Err(AiError::Config(
+                "AI_MAX_CONCURRENT must be at least 1".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            http,
+            in_flight: Arc::new(Semaphore::new(
+                max_concurrent.min(Semaphore::MAX_PERMITS as u64) as usize,
+            )),
+            max_retries: env_number("AI_MAX_RETRIES", 3)?.min(u32::MAX as u64) as u32,
+            base_backoff: Duration::from_millis(env_number("AI_RETRY_BASE_MS", 500)?),
+        })
+    }
+
+    // Sends the request built by `build`, retrying 429s, 5xx responses, timeouts and
+    // connection errors with exponential backoff. Non-success answers become errors.
+    // A concurrency slot is held per attempt, so waiting out a backoff frees it.
+    pub async fn send(
+        &self,
+        build: impl Fn(&Client) -> RequestBuilder,
+    ) -> Result<Response, AiError> {
+        let mut attempt = 0;
+        loop {
+            let permit = self
+                .in_flight
+                .acquire()
+                .await
+                .map_err(|_| AiError::Config("AI client is shut down".to_string()))?;
+            let result = build(&self.http).send().await;
+            drop(permit);
+
+            let error = match result {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    let retry_after = retry_after(&response);
+                    let error = if status == StatusCode::TOO_MANY_REQUESTS {
+                        AiError::RateLimited { retry_after }
+                    } else {
+                        AiError::Api {
+                            status: status.as_u16(),
+                            body: response.text().await.unwrap_or_default(),
+                        }
+                    };
+                    if !is_retryable_status(status) {
+                        return Err(error);
+                    }
+                    (error, retry_after)
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => (AiError::from(e), None),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error.0);
+            }
+            // A server asking for a longer pause than we ever back off is answered
+            // right away instead of holding the request open.
+            if let Some(wait) = error.1
+                && wait > MAX_BACKOFF
+            {
+                return Err(AiError::RateLimited {
+                    retry_after: Some(wait),
+                });
+            }
+            // Respect the server's Retry-After when it asks for longer than our backoff.
+            let backoff = self
+                .base_backoff
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(MAX_BACKOFF);
+            sleep(error.1.map_or(backoff, |wait| wait.max(backoff))).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+pub(super) fn env_number(name: &str, default: u64) -> Result<u64, AiError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| AiError::Config(format!("{} must be a non-negative integer", name))),
+        Err(_) => Ok(default),
+    }
+}
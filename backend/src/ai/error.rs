@@ -0,0 +1,114 @@
+use async_graphql::ErrorExtensions;
+use std::{fmt, time::Duration};
+
+#[derive(Debug)]
+pub enum AiError {
+    // The provider is missing settings or was given invalid ones.
+    Config(String),
+    Http(reqwest::Error),
+    // The provider did not answer within the configured timeout.
+    Timeout,
+    // Either our own limits or the provider's 429s; `retry_after` is a hint when known.
+    RateLimited { retry_after: Option<Duration> },
+    // The caller has used up their monthly token quota.
+    QuotaExceeded { monthly_tokens: i64 },
+    // The project the call was made for has used up its monthly token quota.
+    ProjectQuotaExceeded { monthly_tokens: i64 },
+    // The provider answered with a non-success status.
+    Api { status: u16, body: String },
+    EmptyResponse,
+    // The model's answer did not have the expected shape, even after repair attempts.
+    InvalidOutput(String),
+}
+
+impl AiError {
+    // A stable code for clients, sent as the `code` extension of GraphQL errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AiError::Config(_) => "AI_MISCONFIGURED",
+            AiError::Http(_) => "AI_UNAVAILABLE",
+            AiError::Timeout => "AI_TIMEOUT",
+            AiError::RateLimited { .. } => "RATE_LIMITED",
+            AiError::QuotaExceeded { .. } | AiError::ProjectQuotaExceeded { .. } => {
+                "QUOTA_EXCEEDED"
+            }
+            AiError::Api { .. } => "AI_API_ERROR",
+            AiError::EmptyResponse | AiError::InvalidOutput(_) => "AI_INVALID_OUTPUT",
+        }
+    }
+
+    // Provider errors can carry upstream bodies and settings, so clients only get a
+    // generic message for them; the details are logged.
+    fn client_message(&self) -> String {
+        match self {
+            AiError::Config(_) => "The AI service is not configured correctly".to_string(),
+            AiError::Http(_) => "The AI service could not be reached".to_string(),
+            AiError::Api { .. } => "The AI service returned an error".to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiError::Config(msg) => write!(f, "AI provider misconfigured: {}", msg),
+            AiError::Http(e) => write!(f, "AI request failed: {}", e),
+            AiError::Timeout => write!(f, "AI request timed out"),
+            AiError::RateLimited {
+                retry_after: Some(wait),
+            } => write!(
+                f,
+                "Rate limited: try again in {} seconds",
+                wait.as_secs().max(1)
+            ),
+            AiError::RateLimited { retry_after: None } => {
+                write!(f, "Rate limited: try again later")
+            }
+            AiError::QuotaExceeded { monthly_tokens } => {
+                write!(f, "Monthly AI quota of {} tokens used up", monthly_tokens)
+            }
+            AiError::ProjectQuotaExceeded { monthly_tokens } => write!(
+                f,
+                "The project's monthly AI quota of {} tokens is used up",
+                monthly_tokens
+            ),
+            AiError::Api { status, body } => write!(f, "AI API error {}: {}", status, body),
+            AiError::EmptyResponse => write!(f, "No text found in AI response"),
+            AiError::InvalidOutput(reason) => write!(f, "Unusable AI response: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for AiError {}
+
+impl From<reqwest::Error> for AiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            AiError::Timeout
+        } else {
+            // Request URLs may carry credentials.
+            AiError::Http(e.without_url())
+        }
+    }
+}
+
+impl ErrorExtensions for AiError {
+    fn extend(&self) -> async_graphql::Error {
+        if matches!(
+            self,
+            AiError::Config(_) | AiError::Http(_) | AiError::Api { .. }
+        ) {
+            eprintln!("AI request failed: {}", self);
+        }
+        async_graphql::Error::new(self.client_message()).extend_with(|_, e| {
+            e.set("code", self.code());
+            if let AiError::RateLimited {
+                retry_after: Some(wait),
+            } = self
+            {
+                e.set("retryAfterSeconds", wait.as_secs().max(1));
+            }
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use super::{
+    client::AiClient,
+    error::AiError,
+    prompts::Prompt,
+    provider::{Completion, LlmProvider},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+// --- Structs to match Google's API JSON structure ---
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    system_instruction: Content,
+    contents: Vec<Content>,
+}
+
+#[derive(Serialize)]
+struct Content {
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize)]
+struct Part {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    candidates: Vec<Candidate>,
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    prompt_token_count: Option<i32>,
+    candidates_token_count: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    content: ContentResponse,
+}
+
+#[derive(Deserialize)]
+struct ContentResponse {
+    parts: Vec<PartResponse>,
+}
+
+#[derive(Deserialize)]
+struct PartResponse {
+    text: String,
+}
+
+// --- The provider ---
+
+pub struct GeminiProvider {
+    client: AiClient,
+    api_key: String,
+    model: String,
+}
+
+impl GeminiProvider {
+    pub fn new(client: AiClient, api_key: String, model: String) -> Self {
+        Self {
+            client,
+            api_key,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    async fn complete(&self, prompt: &Prompt) -> Result<Completion, AiError> {
+        // The key goes in a header: URLs end up in error messages and logs.
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent",
+            self.model
+        );
+
+        // Build the request body using our structs
+        let request_body = GeminiRequest {
+            system_instruction: Content {
+                parts: vec![Part {
+                    text: prompt.system.clone(),
+                }],
+            },
+            contents: vec![Content {
+                parts: vec![Part {
+                    text: prompt.user.clone(),
+                }],
+            }],
+        };
+
+        // Non-success statuses come back as errors, after retries where they make sense
+        let response = self
+            .client
+            .send(|http| {
+                http.post(&url)
+                    .header("x-goog-api-key", &self.api_key)
+                    .json(&request_body)
+            })
+            .await?;
+
+        // Deserialize the JSON response into our response structs
+        let gemini_response = response.json::<GeminiResponse>().await?;
+
+        // Extract the text from the response
+        let text = gemini_response
+            .candidates
+            .first()
+            .and_then(|candidate| candidate.content.parts.first())
+            .map(|part| part.text.clone())
+            .ok_or(AiError::EmptyResponse)?;
+
+        let usage = gemini_response.usage_metadata;
+        Ok(Completion {
+            text,
+            prompt_tokens: usage.as_ref().and_then(|u| u.prompt_token_count),
+            completion_tokens: usage.as_ref().and_then(|u| u.candidates_token_count),
+        })
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
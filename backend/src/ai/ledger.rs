@@ -0,0 +1,174 @@
+use super::{
+    client::env_number,
+    error::AiError,
+    prompts::{Prompt, load_prompt},
+    provider::{Completion, LlmProvider, SharedProvider},
+    rate_limit::AiRateLimiter,
+};
+use crate::{auth::current_user, db::DbPool, models::prompt_template::PromptTemplate};
+use async_graphql::{Context, ErrorExtensions};
+use async_trait::async_trait;
+use std::time::Instant;
+use uuid::Uuid;
+
+// The monthly token quota for users without an entry in ai_quotas.
+pub struct AiQuotaConfig {
+    pub default_monthly_tokens: Option<i64>,
+}
+
+impl AiQuotaConfig {
+    // Reads AI_MONTHLY_TOKEN_QUOTA; unset means unlimited.
+    pub fn from_env() -> Result<Self, AiError> {
+        let default_monthly_tokens = match std::env::var("AI_MONTHLY_TOKEN_QUOTA") {
+            Ok(_) => Some(env_number("AI_MONTHLY_TOKEN_QUOTA", 0)? as i64),
+            Err(_) => None,
+        };
+        Ok(Self {
+            default_monthly_tokens,
+        })
+    }
+}
+
+// Wraps the configured provider for one resolver call and records every
+// completion, including repair attempts and failures, in ai_requests.
+pub struct LedgerProvider<'a> {
+    inner: &'a dyn LlmProvider,
+    pool: &'a DbPool,
+    user_id: Uuid,
+    project_id: Option<Uuid>,
+    template: PromptTemplate,
+}
+
+impl LedgerProvider<'_> {
+    // The prompt loaded for this call.
+    pub fn template(&self) -> &PromptTemplate {
+        &self.template
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LedgerProvider<'_> {
+    async fn complete(&self, prompt: &Prompt) -> Result<Completion, AiError> {
+        let started = Instant::now();
+        let result = self.inner.complete(prompt).await;
+        let latency_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+
+        let (prompt_tokens, completion_tokens, error_code) = match &result {
+            Ok(completion) => (completion.prompt_tokens, completion.completion_tokens, None),
+            Err(e) => (None, None, Some(e.code())),
+        };
+        // A failed write must not fail a call the provider has already answered.
+        let recorded = sqlx::query!(
+            "INSERT INTO ai_requests (user_id, project_id, feature, model, prompt_template_id, prompt_tokens, completion_tokens, latency_ms, error_code)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            self.user_id,
+            self.project_id,
+            self.template.name,
+            self.inner.model(),
+            self.template.id,
+            prompt_tokens,
+            completion_tokens,
+            latency_ms,
+            error_code
+        )
+        .execute(self.pool)
+        .await;
+        if let Err(e) = recorded {
+            eprintln!("Failed to record AI usage: {}", e);
+        }
+
+        result
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+}
+
+// The caller's quota for the current month (None if unlimited) and the tokens used so far.
+pub async fn monthly_usage(
+    pool: &DbPool,
+    config: &AiQuotaConfig,
+    user_id: Uuid,
+) -> Result<(Option<i64>, i64), sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            (SELECT monthly_tokens FROM ai_quotas WHERE user_id = $1) AS monthly_tokens,
+            EXISTS(SELECT 1 FROM ai_quotas WHERE user_id = $1) AS "has_override!",
+            (SELECT COALESCE(SUM(COALESCE(prompt_tokens, 0) + COALESCE(completion_tokens, 0)), 0)::BIGINT
+                FROM ai_requests
+                WHERE user_id = $1 AND created_at >= date_trunc('month', NOW())) AS "used!"
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let quota = if row.has_override {
+        row.monthly_tokens
+    } else {
+        config.default_monthly_tokens
+    };
+    Ok((quota, row.used))
+}
+
+// The project's quota for the current month (None if it has none) and the tokens
+// its calls have used so far.
+pub async fn project_monthly_usage(
+    pool: &DbPool,
+    project_id: Uuid,
+) -> Result<(Option<i64>, i64), sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            (SELECT monthly_tokens FROM ai_project_quotas WHERE project_id = $1) AS monthly_tokens,
+            (SELECT COALESCE(SUM(COALESCE(prompt_tokens, 0) + COALESCE(completion_tokens, 0)), 0)::BIGINT
+                FROM ai_requests
+                WHERE project_id = $1 AND created_at >= date_trunc('month', NOW())) AS "used!"
+        "#,
+        project_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok((row.monthly_tokens, row.used))
+}
+
+// Prepares an AI call for the current caller: applies the monthly quota and rate
+// limit, loads the named prompt and returns a provider that records usage. Calls
+// made for a project are also held to the project's quota.
+pub async fn begin_ai_call<'a>(
+    ctx: &Context<'a>,
+    prompt_name: &str,
+    project_id: Option<Uuid>,
+) -> async_graphql::Result<LedgerProvider<'a>> {
+    let pool = ctx.data::<DbPool>()?;
+    let user_id = current_user(ctx)?.id;
+
+    // The quota comes first so that a call refused for it does not use up the
+    // caller's rate-limit allowance.
+    let (quota, used) = monthly_usage(pool, ctx.data::<AiQuotaConfig>()?, user_id).await?;
+    if let Some(monthly_tokens) = quota
+        && used >= monthly_tokens
+    {
+        return Err(AiError::QuotaExceeded { monthly_tokens }.extend());
+    }
+    if let Some(project_id) = project_id
+        && let (Some(monthly_tokens), used) = project_monthly_usage(pool, project_id).await?
+        && used >= monthly_tokens
+    {
+        return Err(AiError::ProjectQuotaExceeded { monthly_tokens }.extend());
+    }
+
+    ctx.data::<AiRateLimiter>()?
+        .acquire(user_id)
+        .map_err(|e| e.extend())?;
+
+    Ok(LedgerProvider {
+        inner: ctx.data::<SharedProvider>()?.as_ref(),
+        pool,
+        user_id,
+        project_id,
+        template: load_prompt(pool, prompt_name).await?,
+    })
+}
@@ -0,0 +1,85 @@
+use super::{
+    error::AiError,
+    prompts::{GENERATE_WORKFLOW, Prompt, SCOPE_PROJECT, SUGGEST_RACI, SUMMARIZE_PROJECT},
+    provider::{Completion, LlmProvider},
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+const SCOPE_RESPONSE: &str = r#"[
+    {"title": "Define requirements", "description": "Agree on scope with stakeholders.", "estimate": "2 days", "suggestedRole": "Product Manager"},
+    {"title": "Build the first version", "description": "Implement the core features.", "estimate": "2 weeks", "suggestedRole": "Engineer"},
+    {"title": "Test and release", "description": "Verify and ship.", "estimate": "3 days", "suggestedRole": "QA Lead"}
+]"#;
+
+const WORKFLOW_RESPONSE: &str = r#"[
+    {"name": "Submit request", "role": "Requester", "dependsOn": null},
+    {"name": "Review request", "role": "Manager", "dependsOn": 1},
+    {"name": "Carry out request", "role": "Specialist", "dependsOn": 2}
+]"#;
+
+const SUMMARY_RESPONSE: &str =
+    "The project is on track. Most tasks are in progress and none are blocked.";
+
+// Returns canned responses without touching the network, for tests and offline
+// development. Each prompt gets an answer in the shape its feature expects.
+pub struct MockProvider;
+
+// RACI suggestions may only name the given candidates, so the first ones are made
+// Responsible, Accountable and Informed.
+fn raci_response(prompt: &Prompt) -> Result<String, AiError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Candidate {
+        user_id: Uuid,
+    }
+
+    let candidates: Vec<Candidate> = prompt
+        .values
+        .get("candidates")
+        .map(|candidates| serde_json::from_str(candidates))
+        .transpose()
+        .map_err(|e| AiError::InvalidOutput(e.to_string()))?
+        .unwrap_or_default();
+    let suggestions: Vec<_> = ["Responsible", "Accountable", "Informed"]
+        .into_iter()
+        .zip(candidates)
+        .map(|(role, candidate)| {
+            json!({
+                "role": role,
+                "userId": candidate.user_id,
+                "rationale": format!("Suggested as {} by the mock provider.", role),
+            })
+        })
+        .collect();
+    Ok(json!(suggestions).to_string())
+}
+
+#[async_trait]
+impl LlmProvider for MockProvider {
+    async fn complete(&self, prompt: &Prompt) -> Result<Completion, AiError> {
+        let text = match prompt.name.as_str() {
+            SCOPE_PROJECT => SCOPE_RESPONSE.to_string(),
+            SUGGEST_RACI => raci_response(prompt)?,
+            GENERATE_WORKFLOW => WORKFLOW_RESPONSE.to_string(),
+            SUMMARIZE_PROJECT => SUMMARY_RESPONSE.to_string(),
+            other => {
+                return Err(AiError::InvalidOutput(format!(
+                    "the mock provider has no response for prompt '{}'",
+                    other
+                )));
+            }
+        };
+        Ok(Completion {
+            text,
+            prompt_tokens: None,
+            completion_tokens: None,
+        })
+    }
+
+    fn model(&self) -> &str {
+        "mock"
+    }
+}
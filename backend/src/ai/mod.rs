@@ -1 +1,17 @@
+pub mod client;
+pub mod error;
+pub mod gemini;
+pub mod ledger;
+pub mod mock;
+pub mod openai;
 pub mod project_scoper;
+pub mod project_summarizer;
+pub mod prompts;
+pub mod provider;
+pub mod raci_suggester;
+pub mod rate_limit;
+pub mod structured;
+pub mod workflow_generator;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,117 @@
+use super::{
+    client::AiClient,
+    error::AiError,
+    prompts::Prompt,
+    provider::{Completion, LlmProvider},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+// --- Structs for the OpenAI chat completions API ---
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct Usage {
+    prompt_tokens: Option<i32>,
+    completion_tokens: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: MessageResponse,
+}
+
+#[derive(Deserialize)]
+struct MessageResponse {
+    content: Option<String>,
+}
+
+// --- The provider ---
+
+// Talks to OpenAI or any server exposing the same API, such as Ollama or llama.cpp.
+pub struct OpenAiProvider {
+    client: AiClient,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(client: AiClient, base_url: String, api_key: Option<String>, model: String) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    async fn complete(&self, prompt: &Prompt) -> Result<Completion, AiError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let request_body = ChatRequest {
+            model: &self.model,
+            messages: vec![
+                ChatMessage {
+                    role: "system",
+                    content: &prompt.system,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: &prompt.user,
+                },
+            ],
+        };
+
+        let response = self
+            .client
+            .send(|http| {
+                let request = http.post(&url).json(&request_body);
+                // Local servers usually run without a key.
+                match &self.api_key {
+                    Some(api_key) => request.bearer_auth(api_key),
+                    None => request,
+                }
+            })
+            .await?;
+
+        let chat_response = response.json::<ChatResponse>().await?;
+
+        let text = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or(AiError::EmptyResponse)?;
+
+        let usage = chat_response.usage;
+        Ok(Completion {
+            text,
+            prompt_tokens: usage.as_ref().and_then(|u| u.prompt_tokens),
+            completion_tokens: usage.as_ref().and_then(|u| u.completion_tokens),
+        })
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
@@ -1,88 +1,50 @@
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::env;
-
-// --- Structs to match Google's API JSON structure ---
-
-#[derive(Serialize)]
-struct GeminiRequest {
-    contents: Vec<Content>,
-}
-
-#[derive(Serialize)]
-struct Content {
-    parts: Vec<Part>,
-}
-
-#[derive(Serialize)]
-struct Part {
-    text: String,
-}
-
-#[derive(Deserialize)]
-struct GeminiResponse {
-    candidates: Vec<Candidate>,
-}
-
-#[derive(Deserialize)]
-struct Candidate {
-    content: ContentResponse,
-}
-
-#[derive(Deserialize)]
-struct ContentResponse {
-    parts: Vec<PartResponse>,
+use super::{error::AiError, provider::LlmProvider, structured::complete_json_array};
+use crate::models::prompt_template::PromptTemplate;
+use async_graphql::{InputObject, SimpleObject};
+use serde::Deserialize;
+
+const SCOPED_TASKS_SHAPE: &str = "a JSON array of objects with the fields \"title\" (required), \"description\", \"estimate\" and \"suggestedRole\"";
+
+#[derive(SimpleObject, InputObject, Deserialize, Clone)]
+#[graphql(input_name = "ScopedTaskInput")]
+#[serde(rename_all = "camelCase")]
+pub struct ScopedTask {
+    pub title: String,
+    pub description: Option<String>,
+    // Free-form effort estimate, e.g. "3 days".
+    pub estimate: Option<String>,
+    pub suggested_role: Option<String>,
+    // The version of the prompt that produced this task. Passed back unchanged when
+    // applying, so the created task records it.
+    #[serde(skip)]
+    pub prompt_version: Option<i32>,
 }
 
-#[derive(Deserialize)]
-struct PartResponse {
-    text: String,
-}
-
-// --- The main function ---
-
 pub async fn scope_project(
-    project_description: String,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let api_key = env::var("GOOGLE_AI_API_KEY").expect("GOOGLE_AI_API_KEY must be set");
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:generateContent?key={}",
-        api_key
-    );
-
-    let prompt = format!(
-        "You are an expert project manager. A user has provided the following project description: '{}'.
-        Based on this description, generate a list of high-level tasks required to complete the project.
-        Return the tasks as a JSON array of strings. For example: [\"Task 1\", \"Task 2\", \"Task 3\"].
-        IMPORTANT: Only output the raw JSON array. Do not include any other text, explanations, or Markdown code blocks like ```json.",
-        project_description
-    );
-
-    // Build the request body using our structs
-    let request_body = GeminiRequest {
-        contents: vec![Content {
-            parts: vec![Part { text: prompt }],
-        }],
-    };
-
-    // Create a client and send the request
-    let client = Client::new();
-    let response = client.post(&url).json(&request_body).send().await?;
-
-    // Check if the request was successful
-    if !response.status().is_success() {
-        return Err(format!("API Error: {}", response.status()).into());
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    project_description: &str,
+) -> Result<Vec<ScopedTask>, AiError> {
+    let prompt = template.render(&[("project_description", project_description)]);
+
+    let mut tasks = complete_json_array(
+        provider,
+        &prompt,
+        SCOPED_TASKS_SHAPE,
+        |tasks: &[ScopedTask]| {
+            if tasks.is_empty() {
+                return Err("the task list is empty".to_string());
+            }
+            if tasks.iter().any(|task| task.title.trim().is_empty()) {
+                return Err("every task needs a non-empty title".to_string());
+            }
+            Ok(())
+        },
+    )
+    .await?;
+
+    for task in &mut tasks {
+        task.prompt_version = Some(template.version);
     }
-
-    // Deserialize the JSON response into our response structs
-    let gemini_response = response.json::<GeminiResponse>().await?;
-
-    // Extract the text from the response
-    if let Some(candidate) = gemini_response.candidates.get(0) {
-        if let Some(part) = candidate.content.parts.get(0) {
-            return Ok(part.text.clone());
-        }
-    }
-
-    Err("No text found in AI response".into())
+    Ok(tasks)
 }
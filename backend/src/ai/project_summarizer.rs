@@ -0,0 +1,64 @@
+use super::{error::AiError, provider::LlmProvider};
+use crate::models::{prompt_template::PromptTemplate, task_status::TaskStatus};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Everything the model sees about a project when writing its status report.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSnapshot {
+    pub name: String,
+    pub description: Option<String>,
+    pub deadline: Option<DateTime<Utc>>,
+    pub status_counts: HashMap<TaskStatus, usize>,
+    pub tasks: Vec<TaskSnapshot>,
+    pub recent_changes: Vec<StatusChangeSnapshot>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSnapshot {
+    pub title: String,
+    pub status: TaskStatus,
+    pub deadline: Option<DateTime<Utc>>,
+    // User ids rather than emails, so no contact details reach the provider.
+    pub responsible: Vec<Uuid>,
+    pub accountable: Vec<Uuid>,
+    // The comment left when the task was last blocked, for tasks that still are.
+    pub blocked_reason: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChangeSnapshot {
+    pub task_title: String,
+    pub from_status: TaskStatus,
+    pub to_status: TaskStatus,
+    pub comment: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+pub async fn summarize_project(
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    snapshot: &ProjectSnapshot,
+    people: &HashMap<Uuid, String>,
+) -> Result<String, AiError> {
+    let snapshot_json =
+        serde_json::to_string(snapshot).map_err(|e| AiError::InvalidOutput(e.to_string()))?;
+    let prompt = template.render(&[("project", &snapshot_json)]);
+
+    let summary = provider.complete(&prompt).await?.text;
+    let summary = summary.trim();
+    if summary.is_empty() {
+        return Err(AiError::EmptyResponse);
+    }
+    // The report names people by the ids it was given.
+    let mut summary = summary.to_string();
+    for (id, email) in people {
+        summary = summary.replace(&id.to_string(), email);
+    }
+    Ok(summary)
+}
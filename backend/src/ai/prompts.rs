@@ -0,0 +1,108 @@
+use crate::{db::DbPool, models::prompt_template::PromptTemplate};
+use std::collections::HashMap;
+
+// Names of the stored prompt templates, one per AI feature.
+pub const SCOPE_PROJECT: &str = "scope_project";
+pub const SUGGEST_RACI: &str = "suggest_raci";
+pub const GENERATE_WORKFLOW: &str = "generate_workflow";
+pub const SUMMARIZE_PROJECT: &str = "summarize_project";
+
+// The placeholders the code fills in for each template.
+const TEMPLATE_VARIABLES: &[(&str, &[&str])] = &[
+    (SCOPE_PROJECT, &["project_description"]),
+    (
+        SUGGEST_RACI,
+        &["task_title", "task_description", "candidates"],
+    ),
+    (GENERATE_WORKFLOW, &["process_description"]),
+    (SUMMARIZE_PROJECT, &["project"]),
+];
+
+// A rendered prompt. Providers send `system` with their system role and `user`
+// as the user message, so user content cannot pose as instructions.
+pub struct Prompt {
+    // The template it was rendered from.
+    pub name: String,
+    pub system: String,
+    pub user: String,
+    // The values the placeholders were filled with, by placeholder name.
+    pub values: HashMap<String, String>,
+}
+
+pub async fn load_prompt(pool: &DbPool, name: &str) -> Result<PromptTemplate, sqlx::Error> {
+    sqlx::query_as!(
+        PromptTemplate,
+        "SELECT id, name, version, system_prompt, user_template, created_by_id, created_at
+        FROM prompt_templates WHERE name = $1 ORDER BY version DESC LIMIT 1",
+        name
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// Checks an edited template before it is stored: the name must be known, the
+// system prompt must not take user content, and the user template may only use
+// the placeholders the code provides.
+pub fn check_template(name: &str, system_prompt: &str, user_template: &str) -> Result<(), String> {
+    let Some((_, variables)) = TEMPLATE_VARIABLES.iter().find(|(known, _)| *known == name) else {
+        return Err(format!("unknown prompt template '{}'", name));
+    };
+    if let Some(placeholder) = placeholders(system_prompt).next() {
+        return Err(format!(
+            "the system prompt cannot contain placeholders, found {{{{{}}}}}",
+            placeholder
+        ));
+    }
+    if let Some(placeholder) = placeholders(user_template).find(|p| !variables.contains(p)) {
+        return Err(format!(
+            "unknown placeholder {{{{{}}}}}; available: {}",
+            placeholder,
+            variables.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+impl PromptTemplate {
+    // Fills the user template's placeholders in a single pass, so placeholder-like
+    // text inside the values is left as it is.
+    pub fn render(&self, values: &[(&str, &str)]) -> Prompt {
+        let mut user = String::with_capacity(self.user_template.len());
+        let mut rest = self.user_template.as_str();
+        while let Some((before, after_open)) = rest.split_once("{{") {
+            user.push_str(before);
+            let Some((key, after_close)) = after_open.split_once("}}") else {
+                rest = after_open;
+                user.push_str("{{");
+                break;
+            };
+            match values.iter().find(|(name, _)| *name == key.trim()) {
+                Some((_, value)) => user.push_str(value),
+                None => {
+                    user.push_str("{{");
+                    user.push_str(key);
+                    user.push_str("}}");
+                }
+            }
+            rest = after_close;
+        }
+        user.push_str(rest);
+
+        Prompt {
+            name: self.name.clone(),
+            system: self.system_prompt.clone(),
+            user,
+            values: values
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split("{{")
+        .skip(1)
+        .filter_map(|part| part.split_once("}}").map(|(key, _)| key.trim()))
+}
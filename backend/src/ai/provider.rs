@@ -0,0 +1,52 @@
+use super::{
+    client::AiClient, error::AiError, gemini::GeminiProvider, mock::MockProvider,
+    openai::OpenAiProvider, prompts::Prompt,
+};
+use async_trait::async_trait;
+use std::{env, sync::Arc};
+
+// A text-completion backend. Resolvers get one from the schema data as `SharedProvider`.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    async fn complete(&self, prompt: &Prompt) -> Result<Completion, AiError>;
+
+    // The model name recorded in the AI usage ledger.
+    fn model(&self) -> &str;
+}
+
+pub struct Completion {
+    pub text: String,
+    // Token counts as reported by the provider, when it reports them.
+    pub prompt_tokens: Option<i32>,
+    pub completion_tokens: Option<i32>,
+}
+
+pub type SharedProvider = Arc<dyn LlmProvider>;
+
+// Builds the provider selected by AI_PROVIDER ("gemini", "openai" or "mock").
+pub fn provider_from_env() -> Result<SharedProvider, AiError> {
+    let kind = env::var("AI_PROVIDER").unwrap_or_else(|_| "gemini".to_string());
+    let client = AiClient::from_env()?;
+
+    match kind.as_str() {
+        "gemini" => {
+            let api_key = env::var("GOOGLE_AI_API_KEY")
+                .map_err(|_| AiError::Config("GOOGLE_AI_API_KEY must be set".to_string()))?;
+            let model = env::var("GEMINI_MODEL").unwrap_or_else(|_| "gemini-2.5-pro".to_string());
+            Ok(Arc::new(GeminiProvider::new(client, api_key, model)))
+        }
+        "openai" => {
+            // Any OpenAI-compatible server works, e.g. Ollama at http://localhost:11434/v1.
+            let base_url = env::var("OPENAI_BASE_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+            let api_key = env::var("OPENAI_API_KEY").ok();
+            let model = env::var("OPENAI_MODEL")
+                .map_err(|_| AiError::Config("OPENAI_MODEL must be set".to_string()))?;
+            Ok(Arc::new(OpenAiProvider::new(
+                client, base_url, api_key, model,
+            )))
+        }
+        "mock" => Ok(Arc::new(MockProvider)),
+        other => Err(AiError::Config(format!("unknown AI_PROVIDER '{}'", other))),
+    }
+}
@@ -0,0 +1,77 @@
+use super::{error::AiError, provider::LlmProvider, structured::complete_json_array};
+use crate::models::{
+    prompt_template::PromptTemplate, raci_role::RaciRole, raci_suggestion::RaciSuggestion,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const SUGGESTIONS_SHAPE: &str = "a JSON array of objects with the fields \"role\" (one of Responsible, Accountable, Consulted, Informed), \"userId\" (a candidate id) and \"rationale\"";
+
+// A person who could be staffed on the task, with their latest growth plan. Only
+// the id identifies them, so no personal details are sent to the AI provider.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaciCandidate {
+    pub user_id: Uuid,
+    pub core_competencies: Option<String>,
+    pub developing_skills: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuggestionOutput {
+    role: RaciRole,
+    user_id: Uuid,
+    rationale: String,
+}
+
+pub async fn suggest_raci(
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    task_title: &str,
+    task_description: Option<&str>,
+    candidates: &[RaciCandidate],
+) -> Result<Vec<RaciSuggestion>, AiError> {
+    let candidates_json =
+        serde_json::to_string(candidates).map_err(|e| AiError::InvalidOutput(e.to_string()))?;
+
+    let prompt = template.render(&[
+        ("task_title", task_title),
+        ("task_description", task_description.unwrap_or("")),
+        ("candidates", &candidates_json),
+    ]);
+
+    let outputs = complete_json_array(
+        provider,
+        &prompt,
+        SUGGESTIONS_SHAPE,
+        |suggestions: &[SuggestionOutput]| match suggestions
+            .iter()
+            .find(|s| !candidates.iter().any(|c| c.user_id == s.user_id))
+        {
+            Some(unknown) => Err(format!("{} is not a candidate id", unknown.user_id)),
+            None => Ok(()),
+        },
+    )
+    .await?;
+
+    // The model lists suggestions best first; number them within each role.
+    let mut next_rank: HashMap<RaciRole, i32> = HashMap::new();
+    let suggestions = outputs
+        .into_iter()
+        .map(|output| {
+            let rank = next_rank.entry(output.role).or_insert(0);
+            *rank += 1;
+            RaciSuggestion {
+                role: output.role,
+                user_id: output.user_id,
+                rank: *rank,
+                rationale: output.rationale,
+                prompt_version: template.version,
+            }
+        })
+        .collect();
+
+    Ok(suggestions)
+}
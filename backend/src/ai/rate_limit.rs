@@ -0,0 +1,166 @@
+use super::{client::env_number, error::AiError};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+// Drop idle per-user buckets once this many are tracked.
+const MAX_TRACKED_USERS: usize = 10_000;
+
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn per_minute(limit: u64) -> Self {
+        Self {
+            capacity: limit as f64,
+            refill_per_sec: limit as f64 / 60.0,
+            tokens: limit as f64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    // How long until a token is available, or None if one is available now.
+    fn wait_time(&self) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            None
+        } else if self.refill_per_sec > 0.0 {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        } else {
+            Some(Duration::MAX)
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.capacity
+    }
+}
+
+// Token buckets limiting AI calls per user and across the whole server.
+pub struct AiRateLimiter {
+    per_user_limit: u64,
+    global: Mutex<TokenBucket>,
+    per_user: Mutex<HashMap<Uuid, TokenBucket>>,
+}
+
+impl AiRateLimiter {
+    pub fn new(per_user_per_minute: u64, global_per_minute: u64) -> Self {
+        Self {
+            per_user_limit: per_user_per_minute,
+            global: Mutex::new(TokenBucket::per_minute(global_per_minute)),
+            per_user: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Reads AI_RATE_PER_USER_PER_MIN (default 10) and AI_RATE_GLOBAL_PER_MIN (default 60).
+    pub fn from_env() -> Result<Self, AiError> {
+        // A bucket that never refills would refuse every call for good.
+        let limit = |name, default| match env_number(name, default)? {
+            0 => Err(AiError::Config(format!("{} must be at least 1", name))),
+            limit => Ok(limit),
+        };
+        Ok(Self::new(
+            limit("AI_RATE_PER_USER_PER_MIN", 10)?,
+            limit("AI_RATE_GLOBAL_PER_MIN", 60)?,
+        ))
+    }
+
+    // Takes one token from both the caller's bucket and the global one, or
+    // fails with `RateLimited` without taking any.
+    pub fn acquire(&self, user_id: Uuid) -> Result<(), AiError> {
+        let now = Instant::now();
+        let mut global = self.global.lock().unwrap();
+        let mut per_user = self.per_user.lock().unwrap();
+
+        if per_user.len() >= MAX_TRACKED_USERS {
+            per_user.retain(|_, bucket| {
+                bucket.refill(now);
+                !bucket.is_full()
+            });
+        }
+        let user = per_user
+            .entry(user_id)
+            .or_insert_with(|| TokenBucket::per_minute(self.per_user_limit));
+
+        global.refill(now);
+        user.refill(now);
+        if let Some(wait) = user.wait_time().max(global.wait_time()) {
+            return Err(AiError::RateLimited {
+                retry_after: Some(wait),
+            });
+        }
+
+        user.tokens -= 1.0;
+        global.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_after(result: Result<(), AiError>) -> Duration {
+        match result {
+            Err(AiError::RateLimited {
+                retry_after: Some(wait),
+            }) => wait,
+            _ => panic!("the call was not rate limited"),
+        }
+    }
+
+    #[test]
+    fn allows_a_burst_up_to_the_limit() {
+        let limiter = AiRateLimiter::new(3, 60);
+        let user = Uuid::new_v4();
+        for _ in 0..3 {
+            assert!(limiter.acquire(user).is_ok());
+        }
+        // One token comes back every 20 seconds at three per minute.
+        let wait = retry_after(limiter.acquire(user));
+        assert!(wait > Duration::from_secs(19) && wait <= Duration::from_secs(20));
+
+        // Other users have their own buckets.
+        assert!(limiter.acquire(Uuid::new_v4()).is_ok());
+    }
+
+    #[test]
+    fn the_global_limit_applies_across_users() {
+        let limiter = AiRateLimiter::new(10, 2);
+        assert!(limiter.acquire(Uuid::new_v4()).is_ok());
+        assert!(limiter.acquire(Uuid::new_v4()).is_ok());
+        retry_after(limiter.acquire(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn buckets_refill_over_time_up_to_capacity() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::per_minute(6);
+        bucket.updated_at = start;
+        bucket.tokens = 0.0;
+        assert_eq!(bucket.wait_time(), Some(Duration::from_secs(10)));
+
+        bucket.refill(start + Duration::from_secs(5));
+        assert!(bucket.wait_time().is_some());
+        bucket.refill(start + Duration::from_secs(10));
+        assert_eq!(bucket.wait_time(), None);
+
+        bucket.refill(start + Duration::from_secs(600));
+        assert_eq!(bucket.tokens, 6.0);
+        assert!(bucket.is_full());
+    }
+}
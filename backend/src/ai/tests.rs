@@ -0,0 +1,137 @@
+// Runs the AI resolvers through the schema with the mock provider. Tests get a
+// fresh database with every migration applied, which also seeds the prompts.
+use super::{
+    ledger::AiQuotaConfig, mock::MockProvider, provider::SharedProvider, rate_limit::AiRateLimiter,
+};
+use crate::{
+    auth::AuthUser,
+    db::DbPool,
+    events::EventBus,
+    graphql::{
+        loaders::Loaders,
+        schema::{AppSchema, MutationRoot, QueryRoot},
+        subscription::SubscriptionRoot,
+    },
+    models::task_status::TransitionRules,
+};
+use async_graphql::{Request, Response, Schema, Variables};
+use chrono::Utc;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn schema(pool: &DbPool) -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(pool.clone())
+        .data(TransitionRules::default())
+        .data(EventBus::from_env(pool))
+        .data(Arc::new(MockProvider) as SharedProvider)
+        .data(AiRateLimiter::new(10, 60))
+        .data(AiQuotaConfig {
+            default_monthly_tokens: None,
+        })
+        .data(Loaders::new(pool))
+        .finish()
+}
+
+// A user owning an empty project. Returns the user and project ids.
+async fn seed(pool: &DbPool) -> (Uuid, Uuid) {
+    let user_id = sqlx::query_scalar!(
+        "INSERT INTO users (email, password_hash) VALUES ('owner@example.com', '-') RETURNING id"
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let project_id = sqlx::query_scalar!(
+        "INSERT INTO projects (name, owner_id) VALUES ('Scoped', $1) RETURNING id",
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'Owner')",
+        project_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    (user_id, project_id)
+}
+
+async fn execute(schema: &AppSchema, user_id: Uuid, query: &str, variables: Value) -> Response {
+    let request = Request::new(query)
+        .variables(Variables::from_json(variables))
+        .data(AuthUser {
+            id: user_id,
+            expires_at: Utc::now() + chrono::Duration::hours(1),
+        });
+    schema.execute(request).await
+}
+
+#[sqlx::test]
+async fn scoped_tasks_can_be_applied_to_a_project(pool: DbPool) {
+    let schema = schema(&pool);
+    let (user_id, project_id) = seed(&pool).await;
+
+    let response = execute(
+        &schema,
+        user_id,
+        "mutation($description: String!) {
+            scopeProjectWithAi(projectDescription: $description) {
+                title description estimate suggestedRole promptVersion
+            }
+        }",
+        json!({ "description": "A customer portal" }),
+    )
+    .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let mut scoped = response.data.into_json().unwrap()["scopeProjectWithAi"].clone();
+    let scoped = scoped.as_array_mut().unwrap();
+    assert_eq!(scoped.len(), 3);
+    assert_eq!(scoped[0]["title"], "Define requirements");
+    assert_eq!(scoped[0]["promptVersion"], 1);
+
+    let recorded = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM ai_requests WHERE user_id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(recorded, Some(1));
+
+    // Titles edited during review are stored trimmed.
+    scoped[2]["title"] = json!("  Test and release  ");
+
+    let response = execute(
+        &schema,
+        user_id,
+        "mutation($projectId: ID!, $tasks: [ScopedTaskInput!]!) {
+            applyAiScopeToProject(projectId: $projectId, tasks: $tasks) { title }
+        }",
+        json!({ "projectId": project_id, "tasks": scoped }),
+    )
+    .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let created = sqlx::query!(
+        r#"
+        SELECT t.title, s.estimate, p.version AS "prompt_version?"
+        FROM tasks t
+        JOIN ai_scoped_tasks s ON s.task_id = t.id
+        LEFT JOIN prompt_templates p ON p.id = s.prompt_template_id
+        WHERE t.project_id = $1 ORDER BY t.title
+        "#,
+        project_id
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(created.len(), 3);
+    assert_eq!(created[0].title, "Build the first version");
+    assert_eq!(created[0].estimate.as_deref(), Some("2 weeks"));
+    assert_eq!(created[0].prompt_version, Some(1));
+    assert_eq!(created[2].title, "Test and release");
+}
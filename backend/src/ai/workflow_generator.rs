@@ -0,0 +1,72 @@
+use super::{error::AiError, provider::LlmProvider, structured::complete_json_array};
+use crate::models::{
+    prompt_template::PromptTemplate,
+    workflow_template_draft::{WorkflowStepDraft, WorkflowTemplateDraft, check_step_graph},
+};
+use async_graphql::ID;
+use serde::Deserialize;
+use uuid::Uuid;
+
+const STEPS_SHAPE: &str = "a JSON array of objects with the fields \"name\" (required), \"role\" and \"dependsOn\" (the 1-based number of an earlier step, or null)";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeneratedStep {
+    name: String,
+    role: Option<String>,
+    depends_on: Option<usize>,
+}
+
+pub async fn generate_workflow_template(
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    name: String,
+    process_description: &str,
+) -> Result<WorkflowTemplateDraft, AiError> {
+    let prompt = template.render(&[("process_description", process_description)]);
+
+    let steps = complete_json_array(
+        provider,
+        &prompt,
+        STEPS_SHAPE,
+        |steps: &[GeneratedStep]| {
+            if steps.is_empty() {
+                return Err("the step list is empty".to_string());
+            }
+            if steps.iter().any(|step| step.name.trim().is_empty()) {
+                return Err("every step needs a non-empty name".to_string());
+            }
+            let edges: Vec<(usize, Option<usize>)> = steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| (index + 1, step.depends_on))
+                .collect();
+            check_step_graph(&edges)
+        },
+    )
+    .await?;
+
+    // Give the draft steps ids so dependencies can refer to them like saved steps do.
+    let ids: Vec<ID> = steps
+        .iter()
+        .map(|_| ID(Uuid::new_v4().to_string()))
+        .collect();
+    let steps = steps
+        .into_iter()
+        .enumerate()
+        .map(|(index, step)| WorkflowStepDraft {
+            id: ids[index].clone(),
+            step_name: step.name,
+            step_order: index as i32 + 1,
+            role: step.role,
+            depends_on_step_id: step.depends_on.map(|number| ids[number - 1].clone()),
+        })
+        .collect();
+
+    Ok(WorkflowTemplateDraft {
+        name,
+        description: Some(process_description.to_string()),
+        steps,
+        prompt_version: Some(template.version),
+    })
+}
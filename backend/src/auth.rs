@@ -0,0 +1,205 @@
+use crate::{
+    db::DbPool,
+    graphql::loaders::Loaders,
+    models::{project_role::ProjectRole, user_role::UserRole},
+};
+use actix_web::{FromRequest, HttpRequest, dev::Payload, error::ErrorUnauthorized, web};
+use async_graphql::{Context, Guard};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use std::future::{Ready, ready};
+use uuid::Uuid;
+
+// How long an issued token stays valid.
+const TOKEN_TTL_HOURS: i64 = 24;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Claims {
+    sub: Uuid,
+    exp: usize,
+}
+
+// Signing and verification keys, built once from JWT_SECRET at startup.
+#[derive(Clone)]
+pub struct JwtKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl JwtKeys {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+        }
+    }
+
+    pub fn create_token(&self, user_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+        let exp = (Utc::now() + Duration::hours(TOKEN_TTL_HOURS)).timestamp() as usize;
+        let claims = Claims { sub: user_id, exp };
+        encode(&Header::default(), &claims, &self.encoding)
+    }
+
+    pub fn verify_token(&self, token: &str) -> Result<AuthUser, jsonwebtoken::errors::Error> {
+        let data = decode::<Claims>(token, &self.decoding, &Validation::default())?;
+        Ok(AuthUser {
+            id: data.claims.sub,
+            expires_at: DateTime::from_timestamp(data.claims.exp as i64, 0).unwrap_or_default(),
+        })
+    }
+}
+
+// The caller identified by the bearer token of the current request.
+#[derive(Clone, Copy)]
+pub struct AuthUser {
+    pub id: Uuid,
+    // When the token stops being valid; long-lived subscriptions end there.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl FromRequest for AuthUser {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let Some(keys) = req.app_data::<web::Data<JwtKeys>>() else {
+            return ready(Err(ErrorUnauthorized("JWT keys not configured")));
+        };
+
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        let result = match token {
+            Some(token) => keys
+                .verify_token(token)
+                .map_err(|_| ErrorUnauthorized("Invalid token")),
+            None => Err(ErrorUnauthorized("Missing bearer token")),
+        };
+        ready(result)
+    }
+}
+
+// Returns the authenticated caller, or an error if the request carried no valid token.
+pub fn current_user(ctx: &Context<'_>) -> async_graphql::Result<AuthUser> {
+    ctx.data_opt::<AuthUser>()
+        .copied()
+        .ok_or_else(|| async_graphql::Error::new("Not authenticated"))
+}
+
+// Allows a field only for callers whose role is at least `min_role`.
+// The role is read from the database so a changed role applies immediately.
+pub struct RoleGuard {
+    min_role: UserRole,
+}
+
+impl RoleGuard {
+    pub fn new(min_role: UserRole) -> Self {
+        Self { min_role }
+    }
+}
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+
+        let role = sqlx::query_scalar!(
+            r#"SELECT role AS "role: UserRole" FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        match role {
+            Some(role) if role >= self.min_role => Ok(()),
+            _ => Err(async_graphql::Error::new(format!(
+                "Requires the {:?} role",
+                self.min_role
+            ))),
+        }
+    }
+}
+
+// Fails unless the caller is a member of the project with at least `min_role`.
+pub async fn require_project_role(
+    ctx: &Context<'_>,
+    project_id: Uuid,
+    min_role: ProjectRole,
+) -> async_graphql::Result<()> {
+    let user_id = current_user(ctx)?.id;
+    // Batched, so that resolving a list of projects checks all memberships in one query.
+    let role = ctx
+        .data::<Loaders>()?
+        .project_roles
+        .load_one((user_id, project_id))
+        .await?;
+    check_project_role(role, min_role)
+}
+
+// Whether the user is a member of the project with at least `min_role`, for checks
+// made outside of a resolver.
+pub async fn has_project_role(
+    pool: &DbPool,
+    user_id: Uuid,
+    project_id: Uuid,
+    min_role: ProjectRole,
+) -> Result<bool, sqlx::Error> {
+    let role = project_role(pool, user_id, project_id).await?;
+    Ok(role.is_some_and(|role| role >= min_role))
+}
+
+async fn project_role(
+    pool: &DbPool,
+    user_id: Uuid,
+    project_id: Uuid,
+) -> Result<Option<ProjectRole>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT role AS "role: ProjectRole" FROM project_members WHERE project_id = $1 AND user_id = $2"#,
+        project_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+// Same as `require_project_role`, for the project that owns the given task.
+pub async fn require_task_role(
+    ctx: &Context<'_>,
+    task_id: Uuid,
+    min_role: ProjectRole,
+) -> async_graphql::Result<()> {
+    let pool = ctx.data::<DbPool>()?;
+    let user_id = current_user(ctx)?.id;
+
+    let role = sqlx::query_scalar!(
+        r#"
+        SELECT m.role AS "role: ProjectRole"
+        FROM tasks t JOIN project_members m ON m.project_id = t.project_id
+        WHERE t.id = $1 AND m.user_id = $2
+        "#,
+        task_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    check_project_role(role, min_role)
+}
+
+fn check_project_role(
+    role: Option<ProjectRole>,
+    min_role: ProjectRole,
+) -> async_graphql::Result<()> {
+    match role {
+        Some(role) if role >= min_role => Ok(()),
+        Some(_) => Err(async_graphql::Error::new(format!(
+            "Requires {:?} access to this project",
+            min_role
+        ))),
+        None => Err(async_graphql::Error::new("Not a member of this project")),
+    }
+}
@@ -0,0 +1,105 @@
+use crate::db::DbPool;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use std::{env, time::Duration};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+// Postgres channel used when events are shared between server instances.
+const NOTIFY_CHANNEL: &str = "clarika_events";
+// Events kept for slow subscribers before they start missing some.
+const BUS_CAPACITY: usize = 1024;
+
+// Something changed that live clients may want to re-fetch.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AppEvent {
+    TaskUpdated { project_id: Uuid, task_id: Uuid },
+    RaciChanged { task_id: Uuid },
+    ProjectChanged { project_id: Uuid },
+}
+
+// In-process broadcast of `AppEvent`s feeding the GraphQL subscriptions. With
+// EVENT_BUS=postgres, events go through LISTEN/NOTIFY so every instance sees them.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<AppEvent>,
+    notify_pool: Option<DbPool>,
+}
+
+impl EventBus {
+    pub fn from_env(pool: &DbPool) -> Self {
+        let (sender, _) = broadcast::channel(BUS_CAPACITY);
+        let use_postgres = env::var("EVENT_BUS").is_ok_and(|kind| kind == "postgres");
+        let bus = Self {
+            sender,
+            notify_pool: use_postgres.then(|| pool.clone()),
+        };
+        if let Some(pool) = &bus.notify_pool {
+            tokio::spawn(forward_notifications(pool.clone(), bus.sender.clone()));
+        }
+        bus
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+
+    // Publishing never fails the mutation that triggered it; a lost event only
+    // means clients refresh later.
+    pub async fn publish(&self, event: AppEvent) {
+        let Some(pool) = &self.notify_pool else {
+            // An error here only means nobody is subscribed.
+            let _ = self.sender.send(event);
+            return;
+        };
+
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                eprintln!("Failed to encode event {:?}: {}", event, e);
+                return;
+            }
+        };
+        if let Err(e) = sqlx::query!("SELECT pg_notify($1, $2)", NOTIFY_CHANNEL, payload)
+            .execute(pool)
+            .await
+        {
+            eprintln!("Failed to publish event {:?}: {}", event, e);
+        }
+    }
+}
+
+// Relays NOTIFY payloads from Postgres into the local bus, reconnecting on errors.
+async fn forward_notifications(pool: DbPool, sender: broadcast::Sender<AppEvent>) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to connect event listener: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(NOTIFY_CHANNEL).await {
+            eprintln!("Failed to listen for events: {}", e);
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str(notification.payload()) {
+                    Ok(event) => {
+                        let _ = sender.send(event);
+                    }
+                    Err(e) => eprintln!("Ignoring malformed event: {}", e),
+                },
+                Err(e) => {
+                    eprintln!("Event listener failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use super::pagination::Page;
+use crate::{
+    db::DbPool,
+    models::{
+        ai_task_scope::AiTaskScope,
+        jira_change::JiraChange,
+        jira_sync_state::JiraSyncState,
+        project_role::ProjectRole,
+        raci_assignment::RaciAssignment,
+        task::{Task, TaskFilter, TaskOrder},
+        task_status::TaskStatus,
+        task_status_change::TaskStatusChange,
+        user::User,
+    },
+};
+use async_graphql::dataloader::{DataLoader, Loader};
+use sqlx::{Postgres, QueryBuilder, Row, postgres::PgRow};
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+// Batch the per-object lookups of nested resolvers into one `= ANY($1)` query per
+// field. The loaders do not cache, so they are shared by all requests and
+// subscriptions without serving stale rows.
+
+pub struct UserLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for UserLoader {
+    type Value = User;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, User>, Self::Error> {
+        let users = sqlx::query_as!(
+            User,
+            r#"SELECT id, email, role AS "role: _" FROM users WHERE id = ANY($1)"#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+}
+
+pub struct TaskLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for TaskLoader {
+    type Value = Task;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Task>, Self::Error> {
+        let tasks = sqlx::query_as!(
+            Task,
+            r#"
+            SELECT id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+            FROM tasks WHERE id = ANY($1)
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tasks.into_iter().map(|task| (task.id, task)).collect())
+    }
+}
+
+// Keyed by (user id, project id); users who are not members have no entry.
+pub struct ProjectRoleLoader {
+    pool: DbPool,
+}
+
+impl Loader<(Uuid, Uuid)> for ProjectRoleLoader {
+    type Value = ProjectRole;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[(Uuid, Uuid)],
+    ) -> Result<HashMap<(Uuid, Uuid), ProjectRole>, Self::Error> {
+        let (user_ids, project_ids): (Vec<Uuid>, Vec<Uuid>) = keys.iter().copied().unzip();
+        let members = sqlx::query!(
+            r#"
+            SELECT m.user_id, m.project_id, m.role AS "role: ProjectRole"
+            FROM project_members m
+            JOIN UNNEST($1::uuid[], $2::uuid[]) AS k(user_id, project_id)
+                ON k.user_id = m.user_id AND k.project_id = m.project_id
+            "#,
+            &user_ids,
+            &project_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(members
+            .into_iter()
+            .map(|member| ((member.user_id, member.project_id), member.role))
+            .collect())
+    }
+}
+
+// The arguments of a `Project.tasks` page.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TaskPageArgs {
+    pub filter: TaskFilter,
+    pub order_by: TaskOrder,
+    pub first: Option<i32>,
+    pub after: Option<String>,
+}
+
+impl TaskPageArgs {
+    pub fn page(&self) -> async_graphql::Result<Page> {
+        Page::new(
+            self.order_by.field.sort_key(),
+            self.order_by.direction,
+            self.first,
+            self.after.clone(),
+        )
+    }
+}
+
+// Keyed by project id and page arguments. Projects asked for the same page share one
+// query that takes each project's page through a LATERAL join; the rows are turned
+// into a connection with `Page::connection`.
+pub struct ProjectTasksLoader {
+    pool: DbPool,
+}
+
+impl Loader<(Uuid, TaskPageArgs)> for ProjectTasksLoader {
+    type Value = Arc<Vec<PgRow>>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[(Uuid, TaskPageArgs)],
+    ) -> Result<HashMap<(Uuid, TaskPageArgs), Self::Value>, Self::Error> {
+        let mut projects_by_args: HashMap<&TaskPageArgs, Vec<Uuid>> = HashMap::new();
+        for (project_id, args) in keys {
+            projects_by_args.entry(args).or_default().push(*project_id);
+        }
+
+        let mut pages = HashMap::new();
+        for (args, project_ids) in projects_by_args {
+            let page = args.page()?;
+            let mut query = QueryBuilder::<Postgres>::new("SELECT page.* FROM UNNEST(");
+            query.push_bind(project_ids);
+            query.push(
+                "::uuid[]) AS p(id) CROSS JOIN LATERAL (SELECT t.id, t.title, t.description, t.project_id, t.assigned_to_id, t.status, t.deadline, t.jira_ticket_id, ",
+            );
+            query.push(page.sort_column());
+            query.push(" FROM tasks t WHERE t.project_id = p.id");
+            args.filter.clone().push_conditions(&mut query)?;
+            page.push_bounds(&mut query, "t.id");
+            query.push(") page");
+
+            let rows = query.build().fetch_all(&self.pool).await?;
+            let mut rows_by_project: HashMap<Uuid, Vec<PgRow>> = HashMap::new();
+            for row in rows {
+                rows_by_project
+                    .entry(row.try_get("project_id")?)
+                    .or_default()
+                    .push(row);
+            }
+            for (project_id, rows) in rows_by_project {
+                pages.insert((project_id, args.clone()), Arc::new(rows));
+            }
+        }
+        Ok(pages)
+    }
+}
+
+// Keyed by task id.
+pub struct TaskRaciLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for TaskRaciLoader {
+    type Value = Vec<RaciAssignment>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<RaciAssignment>>, Self::Error> {
+        let assignments = sqlx::query_as!(
+            RaciAssignment,
+            r#"
+            SELECT user_id, task_id, role AS "role: _"
+            FROM raci_assignments WHERE task_id = ANY($1)
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(group_by(assignments, |assignment| assignment.task_id))
+    }
+}
+
+// Keyed by task id.
+pub struct AiTaskScopeLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for AiTaskScopeLoader {
+    type Value = AiTaskScope;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, AiTaskScope>, Self::Error> {
+        let scopes = sqlx::query_as!(
+            AiTaskScope,
+            r#"
+            SELECT s.task_id, s.estimate, s.suggested_role, p.version AS "prompt_version?"
+            FROM ai_scoped_tasks s
+            LEFT JOIN prompt_templates p ON p.id = s.prompt_template_id
+            WHERE s.task_id = ANY($1)
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(scopes
+            .into_iter()
+            .map(|scope| (scope.task_id, scope))
+            .collect())
+    }
+}
+
+// Keyed by task id.
+pub struct TaskStatusHistoryLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for TaskStatusHistoryLoader {
+    type Value = Vec<TaskStatusChange>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<TaskStatusChange>>, Self::Error> {
+        let history = sqlx::query_as!(
+            TaskStatusChange,
+            r#"
+            SELECT id, task_id, from_status AS "from_status: _", to_status AS "to_status: _",
+                changed_by_id, comment, changed_at
+            FROM task_status_history WHERE task_id = ANY($1) ORDER BY changed_at ASC
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(group_by(history, |change| change.task_id))
+    }
+}
+
+// Keyed by task id: the tasks it depends on.
+pub struct TaskBlockedByLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for TaskBlockedByLoader {
+    type Value = Vec<Task>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<Task>>, Self::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT d.task_id AS key, t.id, t.title, t.description, t.project_id, t.assigned_to_id, t.status AS "status: TaskStatus", t.deadline, t.jira_ticket_id
+            FROM task_dependencies d JOIN tasks t ON t.id = d.depends_on_task_id
+            WHERE d.task_id = ANY($1)
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut tasks: HashMap<Uuid, Vec<Task>> = HashMap::new();
+        for row in rows {
+            tasks.entry(row.key).or_default().push(Task {
+                id: row.id,
+                title: row.title,
+                description: row.description,
+                project_id: row.project_id,
+                assigned_to_id: row.assigned_to_id,
+                status: row.status,
+                deadline: row.deadline,
+                jira_ticket_id: row.jira_ticket_id,
+            });
+        }
+        Ok(tasks)
+    }
+}
+
+// Keyed by task id: the tasks that depend on it.
+pub struct TaskBlocksLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for TaskBlocksLoader {
+    type Value = Vec<Task>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<Task>>, Self::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT d.depends_on_task_id AS key, t.id, t.title, t.description, t.project_id, t.assigned_to_id, t.status AS "status: TaskStatus", t.deadline, t.jira_ticket_id
+            FROM task_dependencies d JOIN tasks t ON t.id = d.task_id
+            WHERE d.depends_on_task_id = ANY($1)
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut tasks: HashMap<Uuid, Vec<Task>> = HashMap::new();
+        for row in rows {
+            tasks.entry(row.key).or_default().push(Task {
+                id: row.id,
+                title: row.title,
+                description: row.description,
+                project_id: row.project_id,
+                assigned_to_id: row.assigned_to_id,
+                status: row.status,
+                deadline: row.deadline,
+                jira_ticket_id: row.jira_ticket_id,
+            });
+        }
+        Ok(tasks)
+    }
+}
+
+// Keyed by task id, newest first.
+pub struct JiraChangesLoader {
+    pool: DbPool,
+}
+
+impl Loader<Uuid> for JiraChangesLoader {
+    type Value = Vec<JiraChange>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<JiraChange>>, Self::Error> {
+        let changes = sqlx::query_as!(
+            JiraChange,
+            r#"
+            SELECT task_id, issue_key, field, old_value, new_value, jira_user, error, created_at
+            FROM jira_changes WHERE task_id = ANY($1) ORDER BY created_at DESC
+            "#,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(group_by(changes, |change| change.task_id))
+    }
+}
+
+// Keyed by (task id, issue key), so a task only sees the sync state of the issue it
+// is linked to now.
+pub struct JiraSyncLoader {
+    pool: DbPool,
+}
+
+impl Loader<(Uuid, String)> for JiraSyncLoader {
+    type Value = JiraSyncState;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[(Uuid, String)],
+    ) -> Result<HashMap<(Uuid, String), JiraSyncState>, Self::Error> {
+        let (task_ids, issue_keys): (Vec<Uuid>, Vec<String>) = keys.iter().cloned().unzip();
+        let states = sqlx::query_as!(
+            JiraSyncState,
+            r#"
+            SELECT s.task_id, s.issue_key, s.jira_status, s.synced_at, s.last_error
+            FROM task_jira_sync s
+            JOIN UNNEST($1::uuid[], $2::text[]) AS k(task_id, issue_key)
+                ON k.task_id = s.task_id AND k.issue_key = s.issue_key
+            "#,
+            &task_ids,
+            &issue_keys
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(states
+            .into_iter()
+            .map(|state| ((state.task_id, state.issue_key.clone()), state))
+            .collect())
+    }
+}
+
+// The loaders registered as schema data.
+pub struct Loaders {
+    pub users: DataLoader<UserLoader>,
+    pub tasks: DataLoader<TaskLoader>,
+    pub project_roles: DataLoader<ProjectRoleLoader>,
+    pub project_tasks: DataLoader<ProjectTasksLoader>,
+    pub task_raci: DataLoader<TaskRaciLoader>,
+    pub ai_task_scopes: DataLoader<AiTaskScopeLoader>,
+    pub task_status_history: DataLoader<TaskStatusHistoryLoader>,
+    pub task_blocked_by: DataLoader<TaskBlockedByLoader>,
+    pub task_blocks: DataLoader<TaskBlocksLoader>,
+    pub jira_changes: DataLoader<JiraChangesLoader>,
+    pub jira_sync: DataLoader<JiraSyncLoader>,
+}
+
+impl Loaders {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            users: DataLoader::new(UserLoader { pool: pool.clone() }, tokio::spawn),
+            tasks: DataLoader::new(TaskLoader { pool: pool.clone() }, tokio::spawn),
+            project_roles: DataLoader::new(ProjectRoleLoader { pool: pool.clone() }, tokio::spawn),
+            project_tasks: DataLoader::new(ProjectTasksLoader { pool: pool.clone() }, tokio::spawn),
+            task_raci: DataLoader::new(TaskRaciLoader { pool: pool.clone() }, tokio::spawn),
+            ai_task_scopes: DataLoader::new(AiTaskScopeLoader { pool: pool.clone() }, tokio::spawn),
+            task_status_history: DataLoader::new(
+                TaskStatusHistoryLoader { pool: pool.clone() },
+                tokio::spawn,
+            ),
+            task_blocked_by: DataLoader::new(
+                TaskBlockedByLoader { pool: pool.clone() },
+                tokio::spawn,
+            ),
+            task_blocks: DataLoader::new(TaskBlocksLoader { pool: pool.clone() }, tokio::spawn),
+            jira_changes: DataLoader::new(JiraChangesLoader { pool: pool.clone() }, tokio::spawn),
+            jira_sync: DataLoader::new(JiraSyncLoader { pool: pool.clone() }, tokio::spawn),
+        }
+    }
+}
+
+fn group_by<T>(rows: Vec<T>, key: impl Fn(&T) -> Uuid) -> HashMap<Uuid, Vec<T>> {
+    let mut groups: HashMap<Uuid, Vec<T>> = HashMap::new();
+    for row in rows {
+        groups.entry(key(&row)).or_default().push(row);
+    }
+    groups
+}
@@ -1 +1,4 @@
+pub mod loaders;
+pub mod pagination;
 pub mod schema;
+pub mod subscription;
@@ -0,0 +1,195 @@
+use crate::db::DbPool;
+use async_graphql::{
+    Enum, Error, Result,
+    connection::{Connection, CursorType, Edge, OpaqueCursor},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, QueryBuilder, Row, postgres::PgRow};
+use uuid::Uuid;
+
+// Lists use keyset pagination: rows are ordered by a sort expression with the id as
+// tiebreaker, and a cursor holds the last row's pair. Every sort option has a
+// matching `(expr, id)` index, so a page is an index range scan however deep it is.
+
+// Page size used when `first` is omitted, and the largest one accepted.
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Copy)]
+pub enum SortKind {
+    Text,
+    // Nullable timestamps sort last, as the expression coalesces them to 'infinity'.
+    Timestamp,
+}
+
+// One sort option of a list.
+#[derive(Clone, Copy)]
+pub struct SortKey {
+    pub name: &'static str,
+    // The indexed expression rows are ordered by.
+    pub expr: &'static str,
+    // The column the cursor value is read back from.
+    pub column: &'static str,
+    pub kind: SortKind,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SortValue {
+    Text(String),
+    Timestamp(Option<DateTime<Utc>>),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Keyset {
+    order: String,
+    value: SortValue,
+    id: Uuid,
+}
+
+pub type Cursor = OpaqueCursor<Keyset>;
+
+pub fn escape_like(pattern: &str) -> String {
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+// A requested page: `first` rows after the `after` cursor in the given order.
+pub struct Page {
+    key: SortKey,
+    direction: SortDirection,
+    first: usize,
+    after: Option<Keyset>,
+}
+
+impl Page {
+    pub fn new(
+        key: SortKey,
+        direction: SortDirection,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<Self> {
+        let first = match first {
+            None => DEFAULT_PAGE_SIZE,
+            Some(n) if (0..=MAX_PAGE_SIZE as i32).contains(&n) => n as usize,
+            Some(_) => {
+                return Err(Error::new(format!(
+                    "first must be between 0 and {MAX_PAGE_SIZE}"
+                )));
+            }
+        };
+        let after = match after {
+            Some(cursor) => Some(
+                Cursor::decode_cursor(&cursor)
+                    .map_err(|_| Error::new("Invalid cursor"))?
+                    .0,
+            ),
+            None => None,
+        };
+        let page = Self {
+            key,
+            direction,
+            first,
+            after,
+        };
+        if let Some(after) = &page.after
+            && after.order != page.order()
+        {
+            return Err(Error::new("Cursor does not match the requested order"));
+        }
+        Ok(page)
+    }
+
+    fn order(&self) -> String {
+        let direction = match self.direction {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        };
+        format!("{}:{direction}", self.key.name)
+    }
+
+    // The select list entry the cursor value is read from; goes last in the select list.
+    pub fn sort_column(&self) -> String {
+        format!("{} AS sort_value", self.key.column)
+    }
+
+    // Completes a `SELECT ... WHERE ...` query with the keyset condition, order and
+    // limit, and runs it. The query must select the row's id as `id`.
+    pub async fn fetch<T>(
+        self,
+        mut query: QueryBuilder<'_, Postgres>,
+        id_column: &str,
+        pool: &DbPool,
+    ) -> Result<Connection<Cursor, T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Sync + async_graphql::OutputType,
+    {
+        self.push_bounds(&mut query, id_column);
+        let rows = query.build().fetch_all(pool).await?;
+        self.connection(&rows)
+    }
+
+    // Appends the keyset condition, order and limit to a `SELECT ... WHERE ...` query,
+    // for callers that run the query themselves.
+    pub fn push_bounds(&self, query: &mut QueryBuilder<'_, Postgres>, id_column: &str) {
+        let (comparison, direction) = match self.direction {
+            SortDirection::Asc => (">", "ASC"),
+            SortDirection::Desc => ("<", "DESC"),
+        };
+        if let Some(after) = &self.after {
+            query.push(format_args!(
+                " AND ({}, {id_column}) {comparison} (",
+                self.key.expr
+            ));
+            match &after.value {
+                SortValue::Text(value) => {
+                    query.push_bind(value.clone());
+                }
+                SortValue::Timestamp(value) => {
+                    query.push("COALESCE(");
+                    query.push_bind(*value);
+                    query.push(", 'infinity'::timestamptz)");
+                }
+            }
+            query.push(", ");
+            query.push_bind(after.id);
+            query.push(")");
+        }
+        query.push(format_args!(
+            " ORDER BY {} {direction}, {id_column} {direction} LIMIT ",
+            self.key.expr
+        ));
+        query.push_bind(self.first as i64 + 1);
+    }
+
+    // Builds the page from rows fetched with `push_bounds`.
+    pub fn connection<T>(&self, rows: &[PgRow]) -> Result<Connection<Cursor, T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Sync + async_graphql::OutputType,
+    {
+        let order = self.order();
+        let mut connection = Connection::new(self.after.is_some(), rows.len() > self.first);
+        for row in rows.iter().take(self.first) {
+            let value = match self.key.kind {
+                SortKind::Text => SortValue::Text(row.try_get("sort_value")?),
+                SortKind::Timestamp => SortValue::Timestamp(row.try_get("sort_value")?),
+            };
+            let cursor = OpaqueCursor(Keyset {
+                order: order.clone(),
+                value,
+                id: row.try_get("id")?,
+            });
+            connection.edges.push(Edge::new(cursor, T::from_row(row)?));
+        }
+        Ok(connection)
+    }
+}
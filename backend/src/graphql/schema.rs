@@ -1,13 +1,56 @@
+use super::{
+    pagination::{Cursor, Page, SortDirection},
+    subscription::SubscriptionRoot,
+};
 use crate::{
-    ai::project_scoper::scope_project,
+    ai::{
+        ledger::{AiQuotaConfig, begin_ai_call, monthly_usage, project_monthly_usage},
+        project_scoper::{ScopedTask, scope_project},
+        prompts::{GENERATE_WORKFLOW, SCOPE_PROJECT, SUGGEST_RACI, check_template},
+        raci_suggester::{RaciCandidate, suggest_raci},
+        workflow_generator::generate_workflow_template,
+    },
+    auth::{JwtKeys, RoleGuard, current_user, require_project_role, require_task_role},
     db::DbPool,
+    events::{AppEvent, EventBus},
+    jira::{client::normalize_issue_key, error::JiraError, import::import_issues, sync::Jira},
     models::{
-        growth_template::GrowthTemplate, project::Project, raci_assignment::RaciAssignment,
-        raci_role::RaciRole, task::Task, task_status::TaskStatus, user::User,
-        workflow_step::WorkflowStep, workflow_template::WorkflowTemplate,
+        ai_usage::{AiProjectQuota, AiQuota, AiUsage, AiUsageGroupBy},
+        growth_template::GrowthTemplate,
+        jira_import::JiraImportResult,
+        notification::{NOTIFICATION_SORT_KEY, Notification, notify_assignment},
+        notification_preferences::{NotificationPreferences, NotificationPreferencesInput},
+        project::{Project, ProjectFilter, ProjectOrder},
+        project_member::{ProjectMember, require_members},
+        project_role::ProjectRole,
+        prompt_template::PromptTemplate,
+        raci_assignment::RaciAssignment,
+        raci_role::RaciRole,
+        raci_suggestion::RaciSuggestion,
+        task::{Task, UpdateTaskInput, change_task_status, check_title, update_task_fields},
+        task_status::{TaskStatus, TransitionRules},
+        user::{User, UserFilter, UserOrder},
+        user_role::UserRole,
+        webhook_delivery::{DEAD_LETTER_SORT_KEY, WebhookDelivery},
+        webhook_subscription::{
+            UpdateWebhookSubscriptionInput, WebhookEventType, WebhookSubscription, check_secret,
+            check_url, event_names, generate_secret,
+        },
+        workflow_step::{StepRoleAssignment, WorkflowStep},
+        workflow_template::{WorkflowTemplate, WorkflowTemplateOrder},
+        workflow_template_draft::WorkflowTemplateDraft,
+    },
+    webhooks::{
+        address::AddressPolicy,
+        event::{WebhookEvent, enqueue},
     },
 };
-use async_graphql::{Context, EmptySubscription, ID, Object, Schema};
+use async_graphql::{
+    Context, ErrorExtensions, ID, MaybeUndefined, Object, Schema, connection::Connection,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, QueryBuilder};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 pub struct QueryRoot;
@@ -18,21 +61,29 @@ impl QueryRoot {
         "Server is up and running!"
     }
 
-    async fn get_projects(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Project>> {
+    async fn get_projects(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: ProjectFilter,
+        #[graphql(default)] order_by: ProjectOrder,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, Project>> {
         let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let page = Page::new(order_by.field.sort_key(), order_by.direction, first, after)?;
 
-        // TODO: Replace with the actual logged-in user's ID
-        let owner_id = Uuid::parse_str("dfbdcf5a-42b0-4814-825e-86e9b1476575").unwrap();
-
-        let projects = sqlx::query_as!(
-            Project,
-            "SELECT id, name, description, deadline, owner_id FROM projects WHERE owner_id = $1",
-            owner_id
-        )
-        .fetch_all(pool)
-        .await?;
+        let mut query = QueryBuilder::new(
+            "SELECT p.id, p.name, p.description, p.deadline, p.owner_id, p.archived_at, ",
+        );
+        query.push(page.sort_column());
+        query.push(
+            " FROM projects p JOIN project_members m ON m.project_id = p.id WHERE m.user_id = ",
+        );
+        query.push_bind(user_id);
+        filter.push_conditions(&mut query);
 
-        Ok(projects)
+        page.fetch(query, "p.id", pool).await
     }
 
     async fn get_project_by_id(
@@ -42,10 +93,11 @@ impl QueryRoot {
     ) -> async_graphql::Result<Option<Project>> {
         let pool = ctx.data::<DbPool>()?;
         let project_id = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_id, ProjectRole::Viewer).await?;
 
         let project = sqlx::query_as!(
             Project,
-            "SELECT id, name, description, deadline, owner_id FROM projects WHERE id = $1",
+            "SELECT id, name, description, deadline, owner_id, archived_at FROM projects WHERE id = $1",
             project_id
         )
         .fetch_optional(pool)
@@ -54,26 +106,108 @@ impl QueryRoot {
         Ok(project)
     }
 
-    async fn get_users(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
+    async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
         let pool = ctx.data::<DbPool>()?;
-        let users = sqlx::query_as!(User, "SELECT id, email FROM users ORDER BY email")
-            .fetch_all(pool)
-            .await?;
-        Ok(users)
+        let user_id = current_user(ctx)?.id;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"SELECT id, email, role AS "role: _" FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(user)
     }
 
-    async fn get_workflow_templates(
+    async fn get_users(
         &self,
         ctx: &Context<'_>,
-    ) -> async_graphql::Result<Vec<WorkflowTemplate>> {
+        #[graphql(default)] filter: UserFilter,
+        #[graphql(default)] order_by: UserOrder,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, User>> {
         let pool = ctx.data::<DbPool>()?;
-        let templates = sqlx::query_as!(
-            WorkflowTemplate,
-            "SELECT id, name, description, created_by_id FROM workflow_templates ORDER BY name"
+        current_user(ctx)?;
+        let page = Page::new(order_by.field.sort_key(), order_by.direction, first, after)?;
+
+        let mut query = QueryBuilder::new("SELECT u.id, u.email, u.role, ");
+        query.push(page.sort_column());
+        query.push(" FROM users u WHERE TRUE");
+        filter.push_conditions(&mut query);
+
+        page.fetch(query, "u.id", pool).await
+    }
+
+    // Ranks the task's project members for each RACI role from their growth plans.
+    async fn suggest_raci(
+        &self,
+        ctx: &Context<'_>,
+        task_id: ID,
+    ) -> async_graphql::Result<Vec<RaciSuggestion>> {
+        let pool = ctx.data::<DbPool>()?;
+        let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Viewer).await?;
+
+        let task = sqlx::query!(
+            "SELECT title, description, project_id FROM tasks WHERE id = $1",
+            task_uuid
+        )
+        .fetch_one(pool)
+        .await?;
+
+        // Each member's most recent growth template, if they have one.
+        let candidates = sqlx::query_as!(
+            RaciCandidate,
+            r#"
+            SELECT u.id AS user_id, g.core_competencies AS "core_competencies?", g.developing_skills AS "developing_skills?"
+            FROM project_members m
+            JOIN users u ON u.id = m.user_id
+            LEFT JOIN LATERAL (
+                SELECT core_competencies, developing_skills FROM growth_templates
+                WHERE user_id = u.id ORDER BY created_at DESC LIMIT 1
+            ) g ON TRUE
+            WHERE m.project_id = $1
+            "#,
+            task.project_id
         )
         .fetch_all(pool)
         .await?;
-        Ok(templates)
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ai = begin_ai_call(ctx, SUGGEST_RACI, Some(task.project_id)).await?;
+        suggest_raci(
+            &ai,
+            ai.template(),
+            &task.title,
+            task.description.as_deref(),
+            &candidates,
+        )
+        .await
+        .map_err(|e| e.extend())
+    }
+
+    async fn get_workflow_templates(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] order_by: WorkflowTemplateOrder,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, WorkflowTemplate>> {
+        let pool = ctx.data::<DbPool>()?;
+        current_user(ctx)?;
+        let page = Page::new(order_by.field.sort_key(), order_by.direction, first, after)?;
+
+        let mut query =
+            QueryBuilder::new("SELECT w.id, w.name, w.description, w.created_by_id, w.version, ");
+        query.push(page.sort_column());
+        query.push(" FROM workflow_templates w WHERE TRUE");
+
+        page.fetch(query, "w.id", pool).await
     }
 
     async fn get_workflow_template_by_id(
@@ -82,11 +216,12 @@ impl QueryRoot {
         id: ID,
     ) -> async_graphql::Result<Option<WorkflowTemplate>> {
         let pool = ctx.data::<DbPool>()?;
+        current_user(ctx)?;
         let template_id = Uuid::parse_str(&id)?;
 
         let template = sqlx::query_as!(
             WorkflowTemplate,
-            "SELECT id, name, description, created_by_id FROM workflow_templates WHERE id = $1",
+            "SELECT id, name, description, created_by_id, version FROM workflow_templates WHERE id = $1",
             template_id
         )
         .fetch_optional(pool)
@@ -94,6 +229,180 @@ impl QueryRoot {
 
         Ok(template)
     }
+
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn ai_usage(
+        &self,
+        ctx: &Context<'_>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        group_by: AiUsageGroupBy,
+    ) -> async_graphql::Result<Vec<AiUsage>> {
+        let pool = ctx.data::<DbPool>()?;
+        let group_by = match group_by {
+            AiUsageGroupBy::User => "user",
+            AiUsageGroupBy::Feature => "feature",
+            AiUsageGroupBy::Model => "model",
+            AiUsageGroupBy::Day => "day",
+        };
+
+        let usage = sqlx::query_as!(
+            AiUsage,
+            r#"
+            SELECT
+                CASE $3
+                    WHEN 'user' THEN COALESCE(u.email, 'deleted user')
+                    WHEN 'feature' THEN r.feature
+                    WHEN 'model' THEN r.model
+                    ELSE to_char(r.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD')
+                END AS "key!",
+                COUNT(*) AS "requests!",
+                COUNT(r.error_code) AS "failed_requests!",
+                COALESCE(SUM(r.prompt_tokens), 0)::BIGINT AS "prompt_tokens!",
+                COALESCE(SUM(r.completion_tokens), 0)::BIGINT AS "completion_tokens!",
+                AVG(r.latency_ms)::FLOAT8 AS "average_latency_ms!"
+            FROM ai_requests r LEFT JOIN users u ON u.id = r.user_id
+            WHERE r.created_at >= $1 AND r.created_at < $2
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            from,
+            to,
+            group_by
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(usage)
+    }
+
+    // The caller's AI token quota and what they have used this month.
+    async fn my_ai_quota(&self, ctx: &Context<'_>) -> async_graphql::Result<AiQuota> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let (monthly_tokens, used_this_month) =
+            monthly_usage(pool, ctx.data::<AiQuotaConfig>()?, user_id).await?;
+        Ok(AiQuota {
+            user_id: ID(user_id.to_string()),
+            monthly_tokens,
+            used_this_month,
+        })
+    }
+
+    // The version in use for each prompt, or every stored version with `all_versions`.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn prompt_templates(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = false)] all_versions: bool,
+    ) -> async_graphql::Result<Vec<PromptTemplate>> {
+        let pool = ctx.data::<DbPool>()?;
+        let templates = sqlx::query_as!(
+            PromptTemplate,
+            "SELECT id, name, version, system_prompt, user_template, created_by_id, created_at
+            FROM prompt_templates p
+            WHERE $1 OR version = (SELECT MAX(version) FROM prompt_templates WHERE name = p.name)
+            ORDER BY name ASC, version DESC",
+            all_versions
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(templates)
+    }
+
+    // The caller's notifications, newest first.
+    async fn my_notifications(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = false)] unread_only: bool,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, Notification>> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let page = Page::new(NOTIFICATION_SORT_KEY, SortDirection::Desc, first, after)?;
+
+        let mut query = QueryBuilder::new(
+            "SELECT n.id, n.kind, n.task_id, n.actor_id, n.role, n.from_status, n.to_status, n.read_at, n.created_at, ",
+        );
+        query.push(page.sort_column());
+        query.push(" FROM notifications n WHERE n.user_id = ");
+        query.push_bind(user_id);
+        if unread_only {
+            query.push(" AND n.read_at IS NULL");
+        }
+
+        page.fetch(query, "n.id", pool).await
+    }
+
+    async fn my_unread_notification_count(&self, ctx: &Context<'_>) -> async_graphql::Result<i64> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM notifications WHERE user_id = $1 AND read_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count)
+    }
+
+    async fn my_notification_preferences(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<NotificationPreferences> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let preferences = sqlx::query_as!(
+            NotificationPreferences,
+            "SELECT assignments, status_changes FROM notification_preferences WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(preferences.unwrap_or_default())
+    }
+
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn webhook_subscriptions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<WebhookSubscription>> {
+        let pool = ctx.data::<DbPool>()?;
+        let subscriptions = sqlx::query_as!(
+            WebhookSubscription,
+            "SELECT id, url, secret, events, active, created_by_id, created_at
+            FROM webhook_subscriptions ORDER BY created_at ASC"
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    // Deliveries that ran out of attempts, most recently failed first.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn webhook_dead_letters(
+        &self,
+        ctx: &Context<'_>,
+        subscription_id: Option<ID>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, WebhookDelivery>> {
+        let pool = ctx.data::<DbPool>()?;
+        let page = Page::new(DEAD_LETTER_SORT_KEY, SortDirection::Desc, first, after)?;
+
+        let mut query = QueryBuilder::new(
+            "SELECT d.id, d.subscription_id, d.event, d.payload, d.status, d.attempts, d.next_attempt_at, \
+            d.last_attempt_at, d.last_status_code, d.last_error, d.delivered_at, d.created_at, ",
+        );
+        query.push(page.sort_column());
+        query.push(" FROM webhook_deliveries d WHERE d.status = 'Dead'");
+        if let Some(subscription_id) = subscription_id {
+            query.push(" AND d.subscription_id = ");
+            query.push_bind(Uuid::parse_str(&subscription_id)?);
+        }
+
+        page.fetch(query, "d.id", pool).await
+    }
 }
 
 pub struct MutationRoot;
@@ -111,7 +420,7 @@ impl MutationRoot {
 
         let new_user = sqlx::query_as!(
             User,
-            "INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id, email",
+            r#"INSERT INTO users (email, password_hash) VALUES ($1, $2) RETURNING id, email, role AS "role: _""#,
             email.to_lowercase(),
             password_hash
         )
@@ -121,6 +430,356 @@ impl MutationRoot {
         Ok(new_user)
     }
 
+    // Overrides the default monthly quota for one user; `monthly_tokens: null` makes
+    // them unlimited and `reset: true` returns them to the default.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn set_ai_quota(
+        &self,
+        ctx: &Context<'_>,
+        user_id: ID,
+        monthly_tokens: Option<i64>,
+        #[graphql(default = false)] reset: bool,
+    ) -> async_graphql::Result<AiQuota> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_uuid = Uuid::parse_str(&user_id)?;
+
+        if reset {
+            sqlx::query!("DELETE FROM ai_quotas WHERE user_id = $1", user_uuid)
+                .execute(pool)
+                .await?;
+        } else {
+            sqlx::query!(
+                "INSERT INTO ai_quotas (user_id, monthly_tokens) VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE SET monthly_tokens = EXCLUDED.monthly_tokens, updated_at = NOW()",
+                user_uuid,
+                monthly_tokens
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        let (monthly_tokens, used_this_month) =
+            monthly_usage(pool, ctx.data::<AiQuotaConfig>()?, user_uuid).await?;
+        Ok(AiQuota {
+            user_id,
+            monthly_tokens,
+            used_this_month,
+        })
+    }
+
+    // Sets the monthly quota shared by everyone's AI calls for a project;
+    // `monthly_tokens: null` removes it.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn set_ai_project_quota(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+        monthly_tokens: Option<i64>,
+    ) -> async_graphql::Result<AiProjectQuota> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+
+        match monthly_tokens {
+            Some(monthly_tokens) => {
+                let result = sqlx::query!(
+                    "INSERT INTO ai_project_quotas (project_id, monthly_tokens) VALUES ($1, $2)
+                    ON CONFLICT (project_id) DO UPDATE SET monthly_tokens = EXCLUDED.monthly_tokens, updated_at = NOW()",
+                    project_uuid,
+                    monthly_tokens
+                )
+                .execute(pool)
+                .await;
+                if let Err(sqlx::Error::Database(e)) = &result
+                    && e.is_foreign_key_violation()
+                {
+                    return Err(async_graphql::Error::new("Project not found"));
+                }
+                result?;
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM ai_project_quotas WHERE project_id = $1",
+                    project_uuid
+                )
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        let (monthly_tokens, used_this_month) = project_monthly_usage(pool, project_uuid).await?;
+        Ok(AiProjectQuota {
+            project_id,
+            monthly_tokens,
+            used_this_month,
+        })
+    }
+
+    // Stores the edit as the next version of the prompt; earlier versions are kept.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn update_prompt_template(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        system_prompt: String,
+        user_template: String,
+    ) -> async_graphql::Result<PromptTemplate> {
+        let pool = ctx.data::<DbPool>()?;
+        let created_by_id = current_user(ctx)?.id;
+        check_template(&name, &system_prompt, &user_template)
+            .map_err(|e| async_graphql::Error::new(format!("Invalid prompt template: {}", e)))?;
+
+        let template = sqlx::query_as!(
+            PromptTemplate,
+            "INSERT INTO prompt_templates (name, version, system_prompt, user_template, created_by_id)
+            SELECT $1::VARCHAR, COALESCE(MAX(version), 0) + 1, $2, $3, $4 FROM prompt_templates WHERE name = $1
+            RETURNING id, name, version, system_prompt, user_template, created_by_id, created_at",
+            name,
+            system_prompt,
+            user_template,
+            created_by_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(template)
+    }
+
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn set_user_role(
+        &self,
+        ctx: &Context<'_>,
+        user_id: ID,
+        role: UserRole,
+    ) -> async_graphql::Result<User> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_uuid = Uuid::parse_str(&user_id)?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"UPDATE users SET role = $1, updated_at = NOW() WHERE id = $2
+            RETURNING id, email, role AS "role: _""#,
+            role as _,
+            user_uuid
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    // Without a secret, one is generated; it can be read back from the subscription.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn create_webhook_subscription(
+        &self,
+        ctx: &Context<'_>,
+        url: String,
+        events: Vec<WebhookEventType>,
+        secret: Option<String>,
+    ) -> async_graphql::Result<WebhookSubscription> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        check_url(ctx.data::<AddressPolicy>()?, &url).await?;
+        let events = event_names(&events)?;
+        let secret = match secret {
+            Some(secret) => {
+                check_secret(&secret)?;
+                secret
+            }
+            None => generate_secret(),
+        };
+
+        let subscription = sqlx::query_as!(
+            WebhookSubscription,
+            "INSERT INTO webhook_subscriptions (url, secret, events, created_by_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, url, secret, events, active, created_by_id, created_at",
+            url,
+            secret,
+            &events,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(subscription)
+    }
+
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn update_webhook_subscription(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        input: UpdateWebhookSubscriptionInput,
+    ) -> async_graphql::Result<WebhookSubscription> {
+        let pool = ctx.data::<DbPool>()?;
+        let subscription_id = Uuid::parse_str(&id)?;
+        if let Some(url) = &input.url {
+            check_url(ctx.data::<AddressPolicy>()?, url).await?;
+        }
+        let events = input.events.as_deref().map(event_names).transpose()?;
+        if let Some(secret) = &input.secret {
+            check_secret(secret)?;
+        }
+
+        let subscription = sqlx::query_as!(
+            WebhookSubscription,
+            "UPDATE webhook_subscriptions
+            SET url = COALESCE($2, url), events = COALESCE($3, events),
+                secret = COALESCE($4, secret), active = COALESCE($5, active), updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, url, secret, events, active, created_by_id, created_at",
+            subscription_id,
+            input.url,
+            events.as_deref(),
+            input.secret,
+            input.active
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("Webhook subscription not found"))?;
+        Ok(subscription)
+    }
+
+    // Its queued and dead deliveries go with it.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn delete_webhook_subscription(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+    ) -> async_graphql::Result<bool> {
+        let pool = ctx.data::<DbPool>()?;
+        let subscription_id = Uuid::parse_str(&id)?;
+
+        let result = sqlx::query!(
+            "DELETE FROM webhook_subscriptions WHERE id = $1",
+            subscription_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Queues a dead (or delivered) delivery again with a fresh set of attempts.
+    #[graphql(guard = "RoleGuard::new(UserRole::Admin)")]
+    async fn redeliver_webhook(
+        &self,
+        ctx: &Context<'_>,
+        delivery_id: ID,
+    ) -> async_graphql::Result<WebhookDelivery> {
+        let pool = ctx.data::<DbPool>()?;
+        let delivery_uuid = Uuid::parse_str(&delivery_id)?;
+
+        let delivery = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'Pending', attempts = 0, next_attempt_at = NOW()
+            WHERE id = $1 AND status <> 'Pending'
+            RETURNING id, subscription_id, event, payload, status AS "status: _", attempts,
+                next_attempt_at, last_attempt_at, last_status_code, last_error, delivered_at, created_at
+            "#,
+            delivery_uuid
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("Webhook delivery not found or already queued"))?;
+        Ok(delivery)
+    }
+
+    async fn set_notification_read(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        read: bool,
+    ) -> async_graphql::Result<Notification> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let notification_id = Uuid::parse_str(&id)?;
+
+        // Marking a read notification as read again keeps the original time.
+        let notification = sqlx::query_as!(
+            Notification,
+            r#"
+            UPDATE notifications
+            SET read_at = CASE WHEN $3 THEN COALESCE(read_at, NOW()) END
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, kind AS "kind: _", task_id, actor_id, role AS "role: _",
+                from_status AS "from_status: _", to_status AS "to_status: _", read_at, created_at
+            "#,
+            notification_id,
+            user_id,
+            read
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("Notification not found"))?;
+        Ok(notification)
+    }
+
+    // Returns how many notifications were unread.
+    async fn mark_all_notifications_read(&self, ctx: &Context<'_>) -> async_graphql::Result<i32> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let result = sqlx::query!(
+            "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() as i32)
+    }
+
+    async fn update_my_notification_preferences(
+        &self,
+        ctx: &Context<'_>,
+        input: NotificationPreferencesInput,
+    ) -> async_graphql::Result<NotificationPreferences> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let preferences = sqlx::query_as!(
+            NotificationPreferences,
+            "INSERT INTO notification_preferences (user_id, assignments, status_changes)
+            VALUES ($1, COALESCE($2, TRUE), COALESCE($3, TRUE))
+            ON CONFLICT (user_id) DO UPDATE
+            SET assignments = COALESCE($2, notification_preferences.assignments),
+                status_changes = COALESCE($3, notification_preferences.status_changes),
+                updated_at = NOW()
+            RETURNING assignments, status_changes",
+            user_id,
+            input.assignments,
+            input.status_changes
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(preferences)
+    }
+
+    // Checks the credentials against the stored bcrypt hash and returns a signed JWT.
+    async fn login(
+        &self,
+        ctx: &Context<'_>,
+        email: String,
+        password: String,
+    ) -> async_graphql::Result<String> {
+        let pool = ctx.data::<DbPool>()?;
+        let keys = ctx.data::<JwtKeys>()?;
+
+        let user = sqlx::query!(
+            "SELECT id, password_hash FROM users WHERE email = $1",
+            email.to_lowercase()
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(user) = user else {
+            return Err(async_graphql::Error::new("Invalid email or password"));
+        };
+        if !bcrypt::verify(password, &user.password_hash)? {
+            return Err(async_graphql::Error::new("Invalid email or password"));
+        }
+
+        let token = keys.create_token(user.id)?;
+        Ok(token)
+    }
+
     async fn create_growth_template(
         &self,
         ctx: &Context<'_>,
@@ -130,9 +789,7 @@ impl MutationRoot {
         how_to_contribute: String,
     ) -> async_graphql::Result<GrowthTemplate> {
         let pool = ctx.data::<DbPool>()?;
-
-        // TODO: Once user login is implemented, get the user_id from the authenticated session token.
-        let user_id = Uuid::parse_str("dfbdcf5a-42b0-4814-825e-86e9b1476575").unwrap();
+        let user_id = current_user(ctx)?.id;
 
         let new_template = sqlx::query_as!(
             GrowthTemplate,
@@ -146,45 +803,275 @@ impl MutationRoot {
         .fetch_one(pool)
         .await?;
 
-        Ok(new_template)
-    }
-
-    async fn scope_project_with_ai(
-        &self,
-        project_description: String,
-    ) -> async_graphql::Result<String> {
-        match scope_project(project_description).await {
-            Ok(tasks) => Ok(tasks),
-            Err(e) => Err(async_graphql::Error::new(format!(
-                "led to scope project with AI: {}",
-                e
-            ))),
-        }
+        Ok(new_template)
+    }
+
+    async fn scope_project_with_ai(
+        &self,
+        ctx: &Context<'_>,
+        project_description: String,
+    ) -> async_graphql::Result<Vec<ScopedTask>> {
+        let ai = begin_ai_call(ctx, SCOPE_PROJECT, None).await?;
+        scope_project(&ai, ai.template(), &project_description)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    // Turns a reviewed AI scope into real tasks, all or nothing.
+    async fn apply_ai_scope_to_project(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+        mut tasks: Vec<ScopedTask>,
+    ) -> async_graphql::Result<Vec<Task>> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Editor).await?;
+
+        // Reviewed lists can be edited, so titles are checked again before anything is created.
+        for task in &mut tasks {
+            task.title = check_title(&task.title)?.to_string();
+        }
+
+        let mut tx = pool.begin().await?;
+        let mut new_tasks = Vec::new();
+        for task in tasks {
+            let new_task = sqlx::query_as!(
+                Task,
+                r#"
+                INSERT INTO tasks (title, description, project_id) VALUES ($1, $2, $3)
+                RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+                "#,
+                task.title,
+                task.description,
+                project_uuid
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query!(
+                "INSERT INTO ai_scoped_tasks (task_id, estimate, suggested_role, prompt_template_id)
+                VALUES ($1, $2, $3, (SELECT id FROM prompt_templates WHERE name = $4 AND version = $5))",
+                new_task.id,
+                task.estimate,
+                task.suggested_role,
+                SCOPE_PROJECT,
+                task.prompt_version
+            )
+            .execute(&mut *tx)
+            .await?;
+            enqueue(&mut *tx, WebhookEvent::TaskCreated(&new_task)).await?;
+            new_tasks.push(new_task);
+        }
+        tx.commit().await?;
+
+        publish_new_tasks(ctx.data::<EventBus>()?, &new_tasks).await;
+        Ok(new_tasks)
+    }
+
+    async fn create_project(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        description: Option<String>,
+    ) -> async_graphql::Result<Project> {
+        let pool = ctx.data::<DbPool>()?;
+        let owner_id = current_user(ctx)?.id;
+
+        let mut tx = pool.begin().await?;
+
+        let new_project = sqlx::query_as!(
+            Project,
+            "INSERT INTO projects (name, description, owner_id) VALUES ($1, $2, $3)
+            RETURNING id, name, description, deadline, owner_id, archived_at",
+            name,
+            description,
+            owner_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // The creator becomes the project's first owner member.
+        sqlx::query!(
+            "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'Owner')",
+            new_project.id,
+            owner_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        enqueue(&mut *tx, WebhookEvent::ProjectCreated(&new_project)).await?;
+        tx.commit().await?;
+
+        Ok(new_project)
+    }
+
+    async fn add_project_member(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+        user_id: ID,
+        role: ProjectRole,
+    ) -> async_graphql::Result<ProjectMember> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        let user_uuid = Uuid::parse_str(&user_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Owner).await?;
+
+        // As with removal, the project's owner keeps their Owner membership.
+        let member = sqlx::query_as!(
+            ProjectMember,
+            r#"
+            INSERT INTO project_members (project_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            WHERE project_members.user_id <> (SELECT owner_id FROM projects WHERE id = $1)
+            RETURNING project_id, user_id, role AS "role: _"
+            "#,
+            project_uuid,
+            user_uuid,
+            role as _
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("The project owner's role cannot be changed"))?;
+
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged {
+                project_id: project_uuid,
+            })
+            .await;
+        Ok(member)
+    }
+
+    async fn remove_project_member(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+        user_id: ID,
+    ) -> async_graphql::Result<bool> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        let user_uuid = Uuid::parse_str(&user_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Owner).await?;
+
+        // The project's owner always keeps their membership.
+        let result = sqlx::query!(
+            "DELETE FROM project_members m USING projects p
+            WHERE m.project_id = p.id AND m.project_id = $1 AND m.user_id = $2
+            AND p.owner_id <> m.user_id",
+            project_uuid,
+            user_uuid
+        )
+        .execute(pool)
+        .await?;
+
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged {
+                project_id: project_uuid,
+            })
+            .await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Partial update: omitted arguments are left untouched, explicit nulls clear the field.
+    async fn update_project(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        name: Option<String>,
+        description: MaybeUndefined<String>,
+        deadline: MaybeUndefined<DateTime<Utc>>,
+    ) -> async_graphql::Result<Project> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_id = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_id, ProjectRole::Editor).await?;
+
+        // Only the given fields are written, in one statement, so concurrent updates
+        // of different fields do not undo each other.
+        let set_description = !description.is_undefined();
+        let set_deadline = !deadline.is_undefined();
+        let updated_project = sqlx::query_as!(
+            Project,
+            "UPDATE projects SET
+                name = COALESCE($1, name),
+                description = CASE WHEN $2 THEN $3 ELSE description END,
+                deadline = CASE WHEN $4 THEN $5 ELSE deadline END,
+                updated_at = NOW()
+            WHERE id = $6
+            RETURNING id, name, description, deadline, owner_id, archived_at",
+            name,
+            set_description,
+            description.take(),
+            set_deadline,
+            deadline.take(),
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged { project_id })
+            .await;
+        Ok(updated_project)
+    }
+
+    async fn archive_project(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Project> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_id = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_id, ProjectRole::Owner).await?;
+
+        // Keep the original timestamp if the project is already archived.
+        let project = sqlx::query_as!(
+            Project,
+            "UPDATE projects SET archived_at = COALESCE(archived_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, description, deadline, owner_id, archived_at",
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged { project_id })
+            .await;
+        Ok(project)
     }
 
-    async fn create_project(
-        &self,
-        ctx: &Context<'_>,
-        name: String,
-        description: Option<String>,
-    ) -> async_graphql::Result<Project> {
+    async fn unarchive_project(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Project> {
         let pool = ctx.data::<DbPool>()?;
+        let project_id = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_id, ProjectRole::Owner).await?;
 
-        // TODO: Replace with the actual logged-in user's ID
-        let owner_id = Uuid::parse_str("dfbdcf5a-42b0-4814-825e-86e9b1476575").unwrap();
-
-        let new_project = sqlx::query_as!(
+        let project = sqlx::query_as!(
             Project,
-            "INSERT INTO projects (name, description, owner_id) VALUES ($1, $2, $3)
-            RETURNING id, name, description, deadline, owner_id",
-            name,
-            description,
-            owner_id
+            "UPDATE projects SET archived_at = NULL, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, description, deadline, owner_id, archived_at",
+            project_id
         )
         .fetch_one(pool)
         .await?;
 
-        Ok(new_project)
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged { project_id })
+            .await;
+        Ok(project)
+    }
+
+    // Tasks, members and RACI rows go with the project through ON DELETE CASCADE.
+    async fn delete_project(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<bool> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_id = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_id, ProjectRole::Owner).await?;
+
+        let result = sqlx::query!("DELETE FROM projects WHERE id = $1", project_id)
+            .execute(pool)
+            .await?;
+
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::ProjectChanged { project_id })
+            .await;
+        Ok(result.rows_affected() > 0)
     }
 
     async fn create_task(
@@ -195,44 +1082,122 @@ impl MutationRoot {
     ) -> async_graphql::Result<Task> {
         let pool = ctx.data::<DbPool>()?;
         let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Editor).await?;
 
+        let mut tx = pool.begin().await?;
         let new_task = sqlx::query_as!(
             Task,
             "INSERT INTO tasks (title, project_id) VALUES ($1, $2)
-            RETURNING id, title, project_id, assigned_to_id, status AS \"status: _\", deadline, jira_ticket_id",
+            RETURNING id, title, description, project_id, assigned_to_id, status AS \"status: _\", deadline, jira_ticket_id",
             title,
             project_uuid
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
         .await?;
+        enqueue(&mut *tx, WebhookEvent::TaskCreated(&new_task)).await?;
+        tx.commit().await?;
 
+        publish_task_updated(ctx, &new_task).await?;
         Ok(new_task)
     }
 
-    async fn update_task_status(
+    async fn update_task(
         &self,
         ctx: &Context<'_>,
         task_id: ID,
-        status: TaskStatus,
+        input: UpdateTaskInput,
+    ) -> async_graphql::Result<Task> {
+        let pool = ctx.data::<DbPool>()?;
+        let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let updated_task = update_task_fields(pool, task_uuid, input).await?;
+
+        publish_task_updated(ctx, &updated_task).await?;
+        Ok(updated_task)
+    }
+
+    async fn assign_task(
+        &self,
+        ctx: &Context<'_>,
+        task_id: ID,
+        user_id: ID,
     ) -> async_graphql::Result<Task> {
         let pool = ctx.data::<DbPool>()?;
         let task_uuid = Uuid::parse_str(&task_id)?;
+        let user_uuid = Uuid::parse_str(&user_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let project_id =
+            sqlx::query_scalar!("SELECT project_id FROM tasks WHERE id = $1", task_uuid)
+                .fetch_one(pool)
+                .await?;
+        require_members(pool, project_id, &[user_uuid]).await?;
 
         let updated_task = sqlx::query_as!(
             Task,
             r#"
-            UPDATE tasks SET status = $1 WHERE id = $2
-            RETURNING id, title, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+            UPDATE tasks SET assigned_to_id = $1, updated_at = NOW() WHERE id = $2
+            RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
             "#,
-            status as _, // Cast the Rust enum to the DB enum type
+            user_uuid,
             task_uuid
         )
         .fetch_one(pool)
         .await?;
 
+        publish_task_updated(ctx, &updated_task).await?;
+        Ok(updated_task)
+    }
+
+    async fn delete_task(&self, ctx: &Context<'_>, task_id: ID) -> async_graphql::Result<bool> {
+        let pool = ctx.data::<DbPool>()?;
+        let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let project_id = sqlx::query_scalar!(
+            "DELETE FROM tasks WHERE id = $1 RETURNING project_id",
+            task_uuid
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(project_id) = project_id else {
+            return Ok(false);
+        };
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::TaskUpdated {
+                project_id,
+                task_id: task_uuid,
+            })
+            .await;
+        Ok(true)
+    }
+
+    async fn update_task_status(
+        &self,
+        ctx: &Context<'_>,
+        task_id: ID,
+        status: TaskStatus,
+        comment: Option<String>,
+    ) -> async_graphql::Result<Task> {
+        let pool = ctx.data::<DbPool>()?;
+        let rules = ctx.data::<TransitionRules>()?;
+        let user_id = current_user(ctx)?.id;
+        let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let updated_task =
+            change_task_status(pool, rules, task_uuid, status, comment, Some(user_id)).await?;
+
+        if let (Some(jira), Some(key)) = (ctx.data_opt::<Jira>(), &updated_task.jira_ticket_id) {
+            jira.push_status(pool.clone(), updated_task.id, key.clone(), status);
+        }
+        publish_task_updated(ctx, &updated_task).await?;
         Ok(updated_task)
     }
 
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
     async fn assign_raci_role(
         &self,
         ctx: &Context<'_>,
@@ -241,8 +1206,25 @@ impl MutationRoot {
         role: RaciRole,
     ) -> async_graphql::Result<RaciAssignment> {
         let pool = ctx.data::<DbPool>()?;
+        let caller_id = current_user(ctx)?.id;
         let user_uuid = Uuid::parse_str(&user_id)?;
         let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let project_id =
+            sqlx::query_scalar!("SELECT project_id FROM tasks WHERE id = $1", task_uuid)
+                .fetch_one(pool)
+                .await?;
+        require_members(pool, project_id, &[user_uuid]).await?;
+
+        let mut tx = pool.begin().await?;
+        let previous_role = sqlx::query_scalar!(
+            r#"SELECT role AS "role: RaciRole" FROM raci_assignments WHERE user_id = $1 AND task_id = $2 FOR UPDATE"#,
+            user_uuid,
+            task_uuid
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
 
         // This SQL performs an "upsert"
         let assignment = sqlx::query_as!(
@@ -257,12 +1239,30 @@ impl MutationRoot {
             task_uuid,
             role as _
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
+        .await?;
+        enqueue(
+            &mut *tx,
+            WebhookEvent::RaciAssigned {
+                task_id: task_uuid,
+                user_id: user_uuid,
+                role,
+            },
+        )
         .await?;
+        // Re-assigning the role someone already holds is not news to them.
+        if previous_role != Some(role) {
+            notify_assignment(&mut tx, task_uuid, user_uuid, role, Some(caller_id)).await?;
+        }
+        tx.commit().await?;
 
+        ctx.data::<EventBus>()?
+            .publish(AppEvent::RaciChanged { task_id: task_uuid })
+            .await;
         Ok(assignment)
     }
 
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
     async fn create_workflow_template(
         &self,
         ctx: &Context<'_>,
@@ -270,13 +1270,12 @@ impl MutationRoot {
         description: Option<String>,
     ) -> async_graphql::Result<WorkflowTemplate> {
         let pool = ctx.data::<DbPool>()?;
-        // TODO: Replace with the actual logged-in user's ID
-        let created_by_id = Uuid::parse_str("dfbdcf5a-42b0-4814-825e-86e9b1476575").unwrap();
+        let created_by_id = current_user(ctx)?.id;
 
         let template = sqlx::query_as!(
             WorkflowTemplate,
             "INSERT INTO workflow_templates (name, description, created_by_id) VALUES ($1, $2, $3)
-            RETURNING id, name, description, created_by_id",
+            RETURNING id, name, description, created_by_id, version",
             name,
             description,
             created_by_id
@@ -286,6 +1285,7 @@ impl MutationRoot {
         Ok(template)
     }
 
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
     async fn add_workflow_step(
         &self,
         ctx: &Context<'_>,
@@ -293,64 +1293,366 @@ impl MutationRoot {
         step_name: String,
         step_order: i32,
         role: Option<String>,
+        depends_on_step_id: Option<ID>,
     ) -> async_graphql::Result<WorkflowStep> {
         let pool = ctx.data::<DbPool>()?;
         let template_uuid = Uuid::parse_str(&template_id)?;
+        let depends_on_uuid = depends_on_step_id
+            .map(|id| Uuid::parse_str(&id))
+            .transpose()?;
+
+        // A step may only depend on an existing step of the same template.
+        if let Some(depends_on_uuid) = depends_on_uuid {
+            let same_template = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM workflow_steps WHERE id = $1 AND template_id = $2) AS "exists!""#,
+                depends_on_uuid,
+                template_uuid
+            )
+            .fetch_one(pool)
+            .await?;
+            if !same_template {
+                return Err(async_graphql::Error::new(
+                    "A step can only depend on another step of the same template",
+                ));
+            }
+        }
+
+        let mut tx = pool.begin().await?;
 
         let step = sqlx::query_as!(
             WorkflowStep,
-            "INSERT INTO workflow_steps (template_id, step_name, step_order, role) VALUES ($1, $2, $3, $4)
+            "INSERT INTO workflow_steps (template_id, step_name, step_order, role, depends_on_step_id)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *",
             template_uuid,
             step_name,
             step_order,
             role,
+            depends_on_uuid,
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // New steps make a new template version for `sync_workflow_template`.
+        sqlx::query!(
+            "UPDATE workflow_templates SET version = version + 1, updated_at = NOW() WHERE id = $1",
+            template_uuid
+        )
+        .execute(&mut *tx)
         .await?;
+
+        tx.commit().await?;
         Ok(step)
     }
 
+    // Drafts a template from a process description; nothing is saved until the
+    // reviewed draft is passed to `save_workflow_template_draft`.
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
+    async fn generate_workflow_template(
+        &self,
+        ctx: &Context<'_>,
+        description: String,
+        #[graphql(default_with = "String::from(\"Generated workflow\")")] name: String,
+    ) -> async_graphql::Result<WorkflowTemplateDraft> {
+        let ai = begin_ai_call(ctx, GENERATE_WORKFLOW, None).await?;
+        generate_workflow_template(&ai, ai.template(), name, &description)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
+    async fn save_workflow_template_draft(
+        &self,
+        ctx: &Context<'_>,
+        draft: WorkflowTemplateDraft,
+    ) -> async_graphql::Result<WorkflowTemplate> {
+        let pool = ctx.data::<DbPool>()?;
+        let created_by_id = current_user(ctx)?.id;
+        draft
+            .validate()
+            .map_err(|e| async_graphql::Error::new(format!("Invalid workflow draft: {}", e)))?;
+
+        let mut tx = pool.begin().await?;
+
+        // Remember which prompt version a generated draft came from.
+        let prompt_template_id = match draft.prompt_version {
+            Some(version) => {
+                sqlx::query_scalar!(
+                    "SELECT id FROM prompt_templates WHERE name = $1 AND version = $2",
+                    GENERATE_WORKFLOW,
+                    version
+                )
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+            None => None,
+        };
+
+        let template = sqlx::query_as!(
+            WorkflowTemplate,
+            "INSERT INTO workflow_templates (name, description, created_by_id, prompt_template_id) VALUES ($1, $2, $3, $4)
+            RETURNING id, name, description, created_by_id, version",
+            draft.name,
+            draft.description,
+            created_by_id,
+            prompt_template_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Insert every step first, then link dependencies once all real ids are known.
+        let mut step_ids: HashMap<&str, Uuid> = HashMap::new();
+        for step in &draft.steps {
+            let step_id = sqlx::query_scalar!(
+                "INSERT INTO workflow_steps (template_id, step_name, step_order, role)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id",
+                template.id,
+                step.step_name,
+                step.step_order,
+                step.role,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            step_ids.insert(step.id.as_str(), step_id);
+        }
+        for step in &draft.steps {
+            if let Some(depends_on) = &step.depends_on_step_id {
+                sqlx::query!(
+                    "UPDATE workflow_steps SET depends_on_step_id = $1 WHERE id = $2",
+                    step_ids[depends_on.as_str()],
+                    step_ids[step.id.as_str()]
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(template)
+    }
+
+    // Applying is atomic and happens once per project; later template steps are
+    // brought in with `sync_workflow_template`. Steps whose role appears in
+    // `role_assignments` get that user as Responsible, and `accountable_user_id`
+    // (the caller by default) as Accountable.
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
     async fn apply_workflow_template_to_project(
         &self,
         ctx: &Context<'_>,
         template_id: ID,
         project_id: ID,
+        #[graphql(default)] role_assignments: Vec<StepRoleAssignment>,
+        accountable_user_id: Option<ID>,
+    ) -> async_graphql::Result<Vec<Task>> {
+        let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
+        let template_uuid = Uuid::parse_str(&template_id)?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Editor).await?;
+        let staffing = Staffing::new(role_assignments, accountable_user_id, user_id)?;
+        require_members(pool, project_uuid, &staffing.users()).await?;
+
+        let mut tx = pool.begin().await?;
+
+        // 1. Lock the template so its steps cannot change while we copy them.
+        let template_version = sqlx::query_scalar!(
+            "SELECT version FROM workflow_templates WHERE id = $1 FOR SHARE",
+            template_uuid
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("Workflow template not found"))?;
+
+        // 2. Record the instance; the unique (project, template) pair rejects re-applying.
+        let instance_id = sqlx::query_scalar!(
+            "INSERT INTO workflow_instances (project_id, template_id, template_version, applied_by_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (project_id, template_id) DO NOTHING
+            RETURNING id",
+            project_uuid,
+            template_uuid,
+            template_version,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            async_graphql::Error::new(
+                "This template is already applied to the project; use syncWorkflowTemplate to add new steps",
+            )
+        })?;
+
+        // 3. Fetch all steps for the template, in order.
+        let steps = sqlx::query_as!(
+            WorkflowStep,
+            "SELECT * FROM workflow_steps WHERE template_id = $1 ORDER BY step_order ASC",
+            template_uuid
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        staffing.check_roles(&steps)?;
+
+        // 4. Create the tasks, their dependencies and RACI assignments.
+        let mut task_for_step = HashMap::new();
+        let new_tasks = create_tasks_for_steps(
+            &mut tx,
+            project_uuid,
+            instance_id,
+            &steps,
+            &staffing,
+            &mut task_for_step,
+        )
+        .await?;
+
+        enqueue(
+            &mut *tx,
+            WebhookEvent::WorkflowApplied {
+                instance_id,
+                project_id: project_uuid,
+                template_id: template_uuid,
+                template_version,
+                applied_by: user_id,
+                tasks: &new_tasks,
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        let bus = ctx.data::<EventBus>()?;
+        publish_new_tasks(bus, &new_tasks).await;
+        bus.publish(AppEvent::ProjectChanged {
+            project_id: project_uuid,
+        })
+        .await;
+
+        // 5. Return the newly created tasks.
+        Ok(new_tasks)
+    }
+
+    // Creates tasks for steps added to the template since it was applied to the project.
+    // Staffing arguments work as in `apply_workflow_template_to_project`.
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
+    async fn sync_workflow_template(
+        &self,
+        ctx: &Context<'_>,
+        template_id: ID,
+        project_id: ID,
+        #[graphql(default)] role_assignments: Vec<StepRoleAssignment>,
+        accountable_user_id: Option<ID>,
     ) -> async_graphql::Result<Vec<Task>> {
         let pool = ctx.data::<DbPool>()?;
+        let user_id = current_user(ctx)?.id;
         let template_uuid = Uuid::parse_str(&template_id)?;
         let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Editor).await?;
+        let staffing = Staffing::new(role_assignments, accountable_user_id, user_id)?;
+        require_members(pool, project_uuid, &staffing.users()).await?;
+
+        let mut tx = pool.begin().await?;
+
+        let instance_id = sqlx::query_scalar!(
+            "SELECT id FROM workflow_instances WHERE project_id = $1 AND template_id = $2 FOR UPDATE",
+            project_uuid,
+            template_uuid
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("This template is not applied to the project"))?;
+
+        let template_version = sqlx::query_scalar!(
+            "SELECT version FROM workflow_templates WHERE id = $1 FOR SHARE",
+            template_uuid
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let linked = sqlx::query!(
+            "SELECT step_id, task_id FROM workflow_instance_tasks WHERE instance_id = $1",
+            instance_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        // Steps whose task was deleted keep their link, so they are not recreated.
+        let mut task_for_step: HashMap<Uuid, Option<Uuid>> = linked
+            .into_iter()
+            .map(|row| (row.step_id, row.task_id))
+            .collect();
 
-        // 1. Fetch all steps for the template, in order.
         let steps = sqlx::query_as!(
             WorkflowStep,
             "SELECT * FROM workflow_steps WHERE template_id = $1 ORDER BY step_order ASC",
             template_uuid
         )
-        .fetch_all(pool)
+        .fetch_all(&mut *tx)
         .await?;
+        staffing.check_roles(&steps)?;
+        let new_steps: Vec<WorkflowStep> = steps
+            .into_iter()
+            .filter(|step| !task_for_step.contains_key(&step.id))
+            .collect();
 
-        // 2. Create a new task for each step.
-        let mut new_tasks = Vec::new();
-        for step in steps {
-            let new_task = sqlx::query_as!(
-                Task,
-                r#"
-                INSERT INTO tasks (title, project_id) VALUES ($1, $2)
-                RETURNING id, title, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
-                "#,
-                step.step_name,
-                project_uuid
-            )
-            .fetch_one(pool)
-            .await?;
-            new_tasks.push(new_task);
-        }
+        let new_tasks = create_tasks_for_steps(
+            &mut tx,
+            project_uuid,
+            instance_id,
+            &new_steps,
+            &staffing,
+            &mut task_for_step,
+        )
+        .await?;
+
+        sqlx::query!(
+            "UPDATE workflow_instances SET template_version = $1, updated_at = NOW() WHERE id = $2",
+            template_version,
+            instance_id
+        )
+        .execute(&mut *tx)
+        .await?;
 
-        // 3. Return the newly created tasks.
+        tx.commit().await?;
+
+        let bus = ctx.data::<EventBus>()?;
+        publish_new_tasks(bus, &new_tasks).await;
+        bus.publish(AppEvent::ProjectChanged {
+            project_id: project_uuid,
+        })
+        .await;
         Ok(new_tasks)
     }
 
+    // Creates tasks for the Jira issues matching `jql`, linked for syncing. Issues
+    // already linked to a task of the project are skipped. Imports can create many
+    // tasks at once, so they are for managers.
+    #[graphql(guard = "RoleGuard::new(UserRole::Manager)")]
+    async fn import_from_jira(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+        jql: String,
+    ) -> async_graphql::Result<JiraImportResult> {
+        let pool = ctx.data::<DbPool>()?;
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Editor).await?;
+        let Some(jira) = ctx.data_opt::<Jira>() else {
+            return Err(JiraError::Config("JIRA_BASE_URL is not set".to_string()).extend());
+        };
+
+        let result = import_issues(jira, pool, project_uuid, &jql).await?;
+
+        let bus = ctx.data::<EventBus>()?;
+        for task in result.issues.iter().filter_map(|issue| issue.task.as_ref()) {
+            bus.publish(AppEvent::TaskUpdated {
+                project_id: task.project_id,
+                task_id: task.id,
+            })
+            .await;
+        }
+        Ok(result)
+    }
+
+    // Links the task to a Jira issue. With the Jira integration configured, the
+    // issue must exist and its status is kept in sync from then on.
     async fn link_jira_ticket(
         &self,
         ctx: &Context<'_>,
@@ -359,21 +1661,250 @@ impl MutationRoot {
     ) -> async_graphql::Result<Task> {
         let pool = ctx.data::<DbPool>()?;
         let task_uuid = Uuid::parse_str(&task_id)?;
+        require_task_role(ctx, task_uuid, ProjectRole::Editor).await?;
+
+        let mut key = normalize_issue_key(&jira_ticket_id).map_err(|e| e.extend())?;
+        let jira = ctx.data_opt::<Jira>();
+        let issue = match jira {
+            Some(jira) => Some(
+                jira.client()
+                    .get_issue(&key)
+                    .await
+                    .map_err(|e| e.extend())?,
+            ),
+            None => None,
+        };
+        // Jira answers for moved issues under their new key.
+        if let Some(issue) = &issue {
+            key = issue.key.clone();
+        }
 
         let updated_task = sqlx::query_as!(
             Task,
             r#"
             UPDATE tasks SET jira_ticket_id = $1 WHERE id = $2
-            RETURNING id, title, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+            RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
             "#,
-            jira_ticket_id,
+            key,
             task_uuid
         )
         .fetch_one(pool)
         .await?;
 
+        if let (Some(jira), Some(issue)) = (jira, &issue) {
+            jira.track(pool, task_uuid, issue).await?;
+        }
+
+        publish_task_updated(ctx, &updated_task).await?;
         Ok(updated_task)
     }
 }
 
-pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+async fn publish_task_updated(ctx: &Context<'_>, task: &Task) -> async_graphql::Result<()> {
+    ctx.data::<EventBus>()?
+        .publish(AppEvent::TaskUpdated {
+            project_id: task.project_id,
+            task_id: task.id,
+        })
+        .await;
+    Ok(())
+}
+
+// New tasks may come with RACI assignments, so both subscriptions hear about them.
+async fn publish_new_tasks(bus: &EventBus, tasks: &[Task]) {
+    for task in tasks {
+        bus.publish(AppEvent::TaskUpdated {
+            project_id: task.project_id,
+            task_id: task.id,
+        })
+        .await;
+        bus.publish(AppEvent::RaciChanged { task_id: task.id })
+            .await;
+    }
+}
+
+// Who gets the RACI roles on tasks created from workflow steps.
+struct Staffing {
+    // Keyed by the normalized step role.
+    responsible_for_role: HashMap<String, Uuid>,
+    accountable: Uuid,
+    // The caller, who is not notified of their own assignments.
+    staffed_by: Uuid,
+}
+
+impl Staffing {
+    fn new(
+        role_assignments: Vec<StepRoleAssignment>,
+        accountable_user_id: Option<ID>,
+        caller_id: Uuid,
+    ) -> async_graphql::Result<Self> {
+        let mut responsible_for_role = HashMap::new();
+        for assignment in role_assignments {
+            let user_id = Uuid::parse_str(&assignment.user_id)?;
+            responsible_for_role.insert(normalize_role(&assignment.role), user_id);
+        }
+        let accountable = match accountable_user_id {
+            Some(id) => Uuid::parse_str(&id)?,
+            None => caller_id,
+        };
+        Ok(Self {
+            responsible_for_role,
+            accountable,
+            staffed_by: caller_id,
+        })
+    }
+
+    // Rejects mapped roles that no step uses, which are almost always typos.
+    fn check_roles(&self, steps: &[WorkflowStep]) -> async_graphql::Result<()> {
+        for role in self.responsible_for_role.keys() {
+            let used = steps
+                .iter()
+                .any(|step| step.role.as_deref().map(normalize_role).as_ref() == Some(role));
+            if !used {
+                return Err(async_graphql::Error::new(format!(
+                    "No step of this template has the role '{}'",
+                    role
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Everyone who may be given a role.
+    fn users(&self) -> Vec<Uuid> {
+        let mut users: Vec<Uuid> = self.responsible_for_role.values().copied().collect();
+        users.push(self.accountable);
+        users
+    }
+
+    fn responsible_for(&self, step: &WorkflowStep) -> Option<Uuid> {
+        let role = normalize_role(step.role.as_deref()?);
+        self.responsible_for_role.get(&role).copied()
+    }
+}
+
+fn normalize_role(role: &str) -> String {
+    role.trim().to_lowercase()
+}
+
+// Creates one task per step, links it to the workflow instance and staffs it.
+// `task_for_step` holds the tasks that already exist for the instance, or None
+// where the task was deleted, and is extended with the new ones, so dependencies
+// can point at both.
+async fn create_tasks_for_steps(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    instance_id: Uuid,
+    steps: &[WorkflowStep],
+    staffing: &Staffing,
+    task_for_step: &mut HashMap<Uuid, Option<Uuid>>,
+) -> Result<Vec<Task>, sqlx::Error> {
+    let mut new_tasks = Vec::new();
+    for step in steps {
+        let responsible = staffing.responsible_for(step);
+
+        let new_task = sqlx::query_as!(
+            Task,
+            r#"
+            INSERT INTO tasks (title, project_id, assigned_to_id) VALUES ($1, $2, $3)
+            RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+            "#,
+            step.step_name,
+            project_id,
+            responsible
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        enqueue(&mut *conn, WebhookEvent::TaskCreated(&new_task)).await?;
+
+        // Responsible wins if the same person would hold both roles.
+        if let Some(responsible) = responsible {
+            sqlx::query!(
+                "INSERT INTO raci_assignments (user_id, task_id, role) VALUES ($1, $2, 'Responsible')",
+                responsible,
+                new_task.id
+            )
+            .execute(&mut *conn)
+            .await?;
+            enqueue(
+                &mut *conn,
+                WebhookEvent::RaciAssigned {
+                    task_id: new_task.id,
+                    user_id: responsible,
+                    role: RaciRole::Responsible,
+                },
+            )
+            .await?;
+            notify_assignment(
+                &mut *conn,
+                new_task.id,
+                responsible,
+                RaciRole::Responsible,
+                Some(staffing.staffed_by),
+            )
+            .await?;
+        }
+        let accountable = sqlx::query!(
+            "INSERT INTO raci_assignments (user_id, task_id, role) VALUES ($1, $2, 'Accountable')
+            ON CONFLICT (user_id, task_id) DO NOTHING",
+            staffing.accountable,
+            new_task.id
+        )
+        .execute(&mut *conn)
+        .await?;
+        if accountable.rows_affected() > 0 {
+            enqueue(
+                &mut *conn,
+                WebhookEvent::RaciAssigned {
+                    task_id: new_task.id,
+                    user_id: staffing.accountable,
+                    role: RaciRole::Accountable,
+                },
+            )
+            .await?;
+            notify_assignment(
+                &mut *conn,
+                new_task.id,
+                staffing.accountable,
+                RaciRole::Accountable,
+                Some(staffing.staffed_by),
+            )
+            .await?;
+        }
+
+        sqlx::query!(
+            "INSERT INTO workflow_instance_tasks (instance_id, step_id, task_id) VALUES ($1, $2, $3)",
+            instance_id,
+            step.id,
+            new_task.id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        task_for_step.insert(step.id, Some(new_task.id));
+        new_tasks.push(new_task);
+    }
+
+    // Carry the step dependencies over to the created tasks.
+    for step in steps {
+        let Some(depends_on_step_id) = step.depends_on_step_id else {
+            continue;
+        };
+        if let (Some(Some(task_id)), Some(Some(depends_on_task_id))) = (
+            task_for_step.get(&step.id),
+            task_for_step.get(&depends_on_step_id),
+        ) {
+            sqlx::query!(
+                "INSERT INTO task_dependencies (task_id, depends_on_task_id) VALUES ($1, $2)",
+                task_id,
+                depends_on_task_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(new_tasks)
+}
+
+pub type AppSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
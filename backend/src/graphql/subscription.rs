@@ -0,0 +1,128 @@
+use crate::{
+    auth::{current_user, has_project_role, require_project_role},
+    db::DbPool,
+    events::{AppEvent, EventBus},
+    models::{
+        live_update::{ProjectUpdate, RaciUpdate, TaskUpdate},
+        project_role::ProjectRole,
+    },
+};
+use async_graphql::{Context, ID, Subscription};
+use futures_util::{Stream, StreamExt, future::ready, stream};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    async fn task_updated(
+        &self,
+        ctx: &Context<'_>,
+        project_id: ID,
+    ) -> async_graphql::Result<impl Stream<Item = TaskUpdate>> {
+        let project_uuid = Uuid::parse_str(&project_id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Viewer).await?;
+
+        let updates = events(ctx)?.filter_map(move |event| {
+            ready(match event {
+                AppEvent::TaskUpdated {
+                    project_id,
+                    task_id,
+                } if project_id == project_uuid => Some(TaskUpdate {
+                    project_id,
+                    task_id,
+                }),
+                _ => None,
+            })
+        });
+        while_member(ctx, project_uuid, updates)
+    }
+
+    async fn raci_changed(
+        &self,
+        ctx: &Context<'_>,
+        task_id: ID,
+    ) -> async_graphql::Result<impl Stream<Item = RaciUpdate>> {
+        let pool = ctx.data::<DbPool>()?;
+        let task_uuid = Uuid::parse_str(&task_id)?;
+        let project_uuid =
+            sqlx::query_scalar!("SELECT project_id FROM tasks WHERE id = $1", task_uuid)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| async_graphql::Error::new("Task not found"))?;
+        require_project_role(ctx, project_uuid, ProjectRole::Viewer).await?;
+
+        let updates = events(ctx)?.filter_map(move |event| {
+            ready(match event {
+                AppEvent::RaciChanged { task_id } if task_id == task_uuid => Some(RaciUpdate {
+                    project_id: project_uuid,
+                    task_id,
+                }),
+                _ => None,
+            })
+        });
+        while_member(ctx, project_uuid, updates)
+    }
+
+    async fn project_changed(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+    ) -> async_graphql::Result<impl Stream<Item = ProjectUpdate>> {
+        let project_uuid = Uuid::parse_str(&id)?;
+        require_project_role(ctx, project_uuid, ProjectRole::Viewer).await?;
+
+        let updates = events(ctx)?.filter_map(move |event| {
+            ready(match event {
+                AppEvent::ProjectChanged { project_id } if project_id == project_uuid => {
+                    Some(ProjectUpdate { project_id })
+                }
+                _ => None,
+            })
+        });
+        while_member(ctx, project_uuid, updates)
+    }
+}
+
+// Every event published from now on. A subscriber that falls behind skips the
+// events it missed rather than ending the stream.
+fn events(ctx: &Context<'_>) -> async_graphql::Result<impl Stream<Item = AppEvent> + use<>> {
+    let receiver = ctx.data::<EventBus>()?.subscribe();
+    Ok(stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }))
+}
+
+// Ends the stream when the caller's token expires, or at the first update after
+// they stopped being a member of the project.
+fn while_member<T>(
+    ctx: &Context<'_>,
+    project_id: Uuid,
+    updates: impl Stream<Item = T>,
+) -> async_graphql::Result<impl Stream<Item = T>> {
+    let pool = ctx.data::<DbPool>()?.clone();
+    let user = current_user(ctx)?;
+    let expires_in = (user.expires_at - chrono::Utc::now())
+        .to_std()
+        .unwrap_or_default();
+
+    Ok(updates
+        .then(move |update| {
+            let pool = pool.clone();
+            async move {
+                let allowed =
+                    has_project_role(&pool, user.id, project_id, ProjectRole::Viewer).await;
+                (matches!(allowed, Ok(true)), update)
+            }
+        })
+        .take_while(|(allowed, _)| ready(*allowed))
+        .map(|(_, update)| update)
+        .take_until(tokio::time::sleep(expires_in)))
+}
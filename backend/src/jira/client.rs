@@ -0,0 +1,374 @@
+use super::error::JiraError;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, header::ACCEPT};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Value, json};
+use std::{env, time::Duration};
+
+// The issue fields every request asks for.
+const ISSUE_FIELDS: &str = "summary,description,status,assignee";
+
+// Jira Cloud takes API tokens as basic auth together with the account email, while
+// Data Center personal access tokens are sent as bearer tokens.
+#[derive(Clone)]
+pub(super) enum Auth {
+    Basic { email: String, token: String },
+    Bearer(String),
+}
+
+// Jira Cloud has retired the offset-paged v2 search in favour of a token-paged v3
+// one, which Data Center does not serve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Deployment {
+    Cloud,
+    DataCenter,
+}
+
+// A client for the Jira REST API. Issues and transitions go through v2, which both
+// Cloud and Data Center serve; searches use whichever API the deployment offers.
+#[derive(Clone)]
+pub struct JiraClient {
+    http: Client,
+    base_url: String,
+    auth: Auth,
+    deployment: Deployment,
+}
+
+#[derive(Deserialize)]
+pub struct JiraIssue {
+    pub key: String,
+    pub fields: IssueFields,
+}
+
+#[derive(Deserialize)]
+pub struct IssueFields {
+    pub summary: String,
+    #[serde(default, deserialize_with = "description_text")]
+    pub description: Option<String>,
+    pub status: JiraStatus,
+    pub assignee: Option<JiraUser>,
+}
+
+#[derive(Deserialize)]
+pub struct JiraStatus {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraUser {
+    // Hidden by Jira Cloud unless the user allows it.
+    pub email_address: Option<String>,
+    pub display_name: Option<String>,
+}
+
+// Where a search page starts: an offset on Data Center, a token from the previous
+// page on Cloud.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PageCursor {
+    Offset(usize),
+    Token(String),
+}
+
+pub struct SearchPage {
+    pub issues: Vec<JiraIssue>,
+    // How many issues match the query in all. Only Data Center reports it.
+    pub total: Option<usize>,
+    // None on the last page.
+    pub next: Option<PageCursor>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OffsetSearch {
+    start_at: usize,
+    total: usize,
+    issues: Vec<JiraIssue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenSearch {
+    issues: Vec<JiraIssue>,
+    next_page_token: Option<String>,
+    #[serde(default)]
+    is_last: bool,
+}
+
+#[derive(Deserialize)]
+struct Transitions {
+    transitions: Vec<Transition>,
+}
+
+#[derive(Deserialize)]
+struct Transition {
+    id: String,
+    to: JiraStatus,
+}
+
+impl JiraClient {
+    // Reads JIRA_BASE_URL, JIRA_API_TOKEN, JIRA_EMAIL, JIRA_DEPLOYMENT and
+    // JIRA_TIMEOUT_SECS. Returns None when JIRA_BASE_URL is unset, which leaves the
+    // integration off. JIRA_DEPLOYMENT is "cloud" or "datacenter"; without it, sites
+    // on atlassian.net are taken to be Cloud.
+    pub fn from_env() -> Result<Option<Self>, JiraError> {
+        let Ok(base_url) = env::var("JIRA_BASE_URL") else {
+            return Ok(None);
+        };
+        let token = env::var("JIRA_API_TOKEN")
+            .map_err(|_| JiraError::Config("JIRA_API_TOKEN must be set".to_string()))?;
+        let auth = match env::var("JIRA_EMAIL") {
+            Ok(email) => Auth::Basic { email, token },
+            Err(_) => Auth::Bearer(token),
+        };
+        let timeout = match env::var("JIRA_TIMEOUT_SECS") {
+            Ok(value) => value.parse().map_err(|_| {
+                JiraError::Config("JIRA_TIMEOUT_SECS must be a non-negative integer".to_string())
+            })?,
+            Err(_) => 30,
+        };
+        let deployment = match env::var("JIRA_DEPLOYMENT") {
+            Ok(value) => match value.to_lowercase().as_str() {
+                "cloud" => Deployment::Cloud,
+                "datacenter" | "server" => Deployment::DataCenter,
+                _ => {
+                    return Err(JiraError::Config(
+                        "JIRA_DEPLOYMENT must be 'cloud' or 'datacenter'".to_string(),
+                    ));
+                }
+            },
+            Err(_) => detect_deployment(&base_url)?,
+        };
+        Self::new(&base_url, auth, deployment, Duration::from_secs(timeout)).map(Some)
+    }
+
+    pub(super) fn new(
+        base_url: &str,
+        auth: Auth,
+        deployment: Deployment,
+        timeout: Duration,
+    ) -> Result<Self, JiraError> {
+        let http = Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+            deployment,
+        })
+    }
+
+    // A v2 request, relative to /rest/api/2/.
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.request_api(method, 2, path)
+    }
+
+    fn request_api(&self, method: Method, version: u8, path: &str) -> RequestBuilder {
+        let request = self
+            .http
+            .request(
+                method,
+                format!("{}/rest/api/{}/{}", self.base_url, version, path),
+            )
+            .header(ACCEPT, "application/json");
+        match &self.auth {
+            Auth::Basic { email, token } => request.basic_auth(email, Some(token)),
+            Auth::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+
+    pub async fn get_issue(&self, key: &str) -> Result<JiraIssue, JiraError> {
+        let response = self
+            .request(Method::GET, &format!("issue/{}", key))
+            .query(&[("fields", ISSUE_FIELDS)])
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(JiraError::IssueNotFound(key.to_string()));
+        }
+        Ok(check(response).await?.json().await?)
+    }
+
+    // One page of the issues matching `jql`, from the start when `page` is None.
+    // Data Center skips unknown issue keys in the query rather than failing it.
+    pub async fn search(
+        &self,
+        jql: &str,
+        page: Option<&PageCursor>,
+        max_results: usize,
+    ) -> Result<SearchPage, JiraError> {
+        let fields: Vec<&str> = ISSUE_FIELDS.split(',').collect();
+        match self.deployment {
+            Deployment::Cloud => {
+                let mut body = json!({
+                    "jql": jql,
+                    "maxResults": max_results,
+                    "fields": fields,
+                });
+                if let Some(PageCursor::Token(token)) = page {
+                    body["nextPageToken"] = json!(token);
+                }
+                let response = self
+                    .request_api(Method::POST, 3, "search/jql")
+                    .json(&body)
+                    .send()
+                    .await?;
+                let found: TokenSearch = check(response).await?.json().await?;
+                let next = match found.next_page_token {
+                    Some(token) if !found.is_last => Some(PageCursor::Token(token)),
+                    _ => None,
+                };
+                Ok(SearchPage {
+                    issues: found.issues,
+                    total: None,
+                    next,
+                })
+            }
+            Deployment::DataCenter => {
+                let start_at = match page {
+                    Some(PageCursor::Offset(offset)) => *offset,
+                    _ => 0,
+                };
+                let body = json!({
+                    "jql": jql,
+                    "startAt": start_at,
+                    "maxResults": max_results,
+                    "fields": fields,
+                    "validateQuery": "warn",
+                });
+                let response = self
+                    .request(Method::POST, "search")
+                    .json(&body)
+                    .send()
+                    .await?;
+                let found: OffsetSearch = check(response).await?.json().await?;
+                let end = found.start_at + found.issues.len();
+                let next = (!found.issues.is_empty() && end < found.total)
+                    .then_some(PageCursor::Offset(end));
+                Ok(SearchPage {
+                    issues: found.issues,
+                    total: Some(found.total),
+                    next,
+                })
+            }
+        }
+    }
+
+    // Moves the issue into the status named `status` through whichever of its
+    // workflow transitions leads there.
+    pub async fn transition_to(&self, key: &str, status: &str) -> Result<(), JiraError> {
+        let path = format!("issue/{}/transitions", key);
+        let response = self.request(Method::GET, &path).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(JiraError::IssueNotFound(key.to_string()));
+        }
+        let available: Transitions = check(response).await?.json().await?;
+
+        let Some(transition) = available
+            .transitions
+            .iter()
+            .find(|transition| transition.to.name.eq_ignore_ascii_case(status))
+        else {
+            let issue = self.get_issue(key).await?;
+            if issue.fields.status.name.eq_ignore_ascii_case(status) {
+                return Ok(());
+            }
+            return Err(JiraError::NoTransition {
+                key: key.to_string(),
+                from: issue.fields.status.name,
+                to: status.to_string(),
+            });
+        };
+
+        let response = self
+            .request(Method::POST, &path)
+            .json(&json!({ "transition": { "id": transition.id } }))
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+}
+
+async fn check(response: Response) -> Result<Response, JiraError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    Err(JiraError::Api {
+        status: status.as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
+
+fn detect_deployment(base_url: &str) -> Result<Deployment, JiraError> {
+    let url = reqwest::Url::parse(base_url)
+        .map_err(|_| JiraError::Config("JIRA_BASE_URL must be an absolute URL".to_string()))?;
+    let cloud = url
+        .host_str()
+        .is_some_and(|host| host.to_lowercase().ends_with(".atlassian.net"));
+    Ok(if cloud {
+        Deployment::Cloud
+    } else {
+        Deployment::DataCenter
+    })
+}
+
+// v2 sends descriptions as wiki markup, v3 as an Atlassian Document Format tree.
+// Either way the task gets plain text.
+fn description_text<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(text)) => Some(text),
+        Some(document @ Value::Object(_)) => {
+            let mut text = String::new();
+            adf_text(&document, &mut text);
+            let text = text.trim_end();
+            (!text.is_empty()).then(|| text.to_string())
+        }
+        _ => None,
+    })
+}
+
+// Appends the text of an ADF node, ending block nodes with a line break.
+fn adf_text(node: &Value, out: &mut String) {
+    let kind = node.get("type").and_then(Value::as_str).unwrap_or_default();
+    match kind {
+        "text" => out.push_str(node.get("text").and_then(Value::as_str).unwrap_or_default()),
+        "hardBreak" => out.push('\n'),
+        "mention" | "emoji" | "status" => {
+            let attrs = node.get("attrs");
+            let text = attrs
+                .and_then(|attrs| attrs.get("text").or_else(|| attrs.get("shortName")))
+                .and_then(Value::as_str);
+            out.push_str(text.unwrap_or_default());
+        }
+        _ => {}
+    }
+    if let Some(children) = node.get("content").and_then(Value::as_array) {
+        for child in children {
+            adf_text(child, out);
+        }
+    }
+    if matches!(kind, "paragraph" | "heading" | "codeBlock" | "rule") && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+// Uppercases and checks a key like "proj-123". Keys go into request paths and JQL,
+// so anything else is rejected.
+pub fn normalize_issue_key(key: &str) -> Result<String, JiraError> {
+    let key = key.trim().to_uppercase();
+    let valid = key.split_once('-').is_some_and(|(project, number)| {
+        project.starts_with(|c: char| c.is_ascii_alphabetic())
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    });
+    if !valid {
+        return Err(JiraError::InvalidKey(key));
+    }
+    Ok(key)
+}
@@ -0,0 +1,96 @@
+use async_graphql::ErrorExtensions;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum JiraError {
+    // The integration is missing settings or was given invalid ones.
+    Config(String),
+    Http(reqwest::Error),
+    // Not something Jira would accept as an issue key, e.g. "PROJ-123".
+    InvalidKey(String),
+    IssueNotFound(String),
+    // The issue's workflow offers no transition into the mapped status.
+    NoTransition {
+        key: String,
+        from: String,
+        to: String,
+    },
+    // An import query matched more issues than one import may create.
+    TooManyIssues {
+        limit: usize,
+    },
+    // Jira answered with a non-success status.
+    Api {
+        status: u16,
+        body: String,
+    },
+}
+
+impl JiraError {
+    // A stable code for clients, sent as the `code` extension of GraphQL errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            JiraError::Config(_) => "JIRA_MISCONFIGURED",
+            JiraError::Http(_) => "JIRA_UNAVAILABLE",
+            JiraError::InvalidKey(_) => "JIRA_INVALID_KEY",
+            JiraError::IssueNotFound(_) => "JIRA_ISSUE_NOT_FOUND",
+            JiraError::NoTransition { .. } => "JIRA_NO_TRANSITION",
+            JiraError::TooManyIssues { .. } => "JIRA_IMPORT_TOO_LARGE",
+            JiraError::Api { .. } => "JIRA_API_ERROR",
+        }
+    }
+
+    // Jira errors can carry response bodies and settings, so clients only get a
+    // generic message for them; the details are logged.
+    fn client_message(&self) -> String {
+        match self {
+            JiraError::Config(_) => "The Jira integration is not configured correctly".to_string(),
+            JiraError::Http(_) => "Jira could not be reached".to_string(),
+            JiraError::Api { .. } => "Jira returned an error".to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for JiraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JiraError::Config(msg) => write!(f, "Jira integration misconfigured: {}", msg),
+            JiraError::Http(e) => write!(f, "Jira request failed: {}", e),
+            JiraError::InvalidKey(key) => write!(f, "'{}' is not a Jira issue key", key),
+            JiraError::IssueNotFound(key) => write!(f, "Jira issue {} not found", key),
+            JiraError::NoTransition { key, from, to } => write!(
+                f,
+                "Jira issue {} has no transition from '{}' to '{}'",
+                key, from, to
+            ),
+            JiraError::TooManyIssues { limit } => write!(
+                f,
+                "The query matches more than {} issues, the most one import may create",
+                limit
+            ),
+            JiraError::Api { status, body } => write!(f, "Jira API error {}: {}", status, body),
+        }
+    }
+}
+
+impl std::error::Error for JiraError {}
+
+impl From<reqwest::Error> for JiraError {
+    fn from(e: reqwest::Error) -> Self {
+        JiraError::Http(e)
+    }
+}
+
+impl ErrorExtensions for JiraError {
+    fn extend(&self) -> async_graphql::Error {
+        if matches!(
+            self,
+            JiraError::Config(_) | JiraError::Http(_) | JiraError::Api { .. }
+        ) {
+            eprintln!("Jira request failed: {}", self);
+        }
+        async_graphql::Error::new(self.client_message())
+            .extend_with(|_, e| e.set("code", self.code()))
+    }
+}
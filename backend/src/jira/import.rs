@@ -0,0 +1,198 @@
+use super::{client::JiraIssue, error::JiraError, sync::Jira};
+use crate::{
+    db::DbPool,
+    models::{
+        jira_import::{JiraImportIssue, JiraImportOutcome, JiraImportResult},
+        task::Task,
+        task_status::TaskStatus,
+    },
+    webhooks::event::{WebhookEvent, enqueue},
+};
+use async_graphql::ErrorExtensions;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// The most issues Jira returns per search page.
+const PAGE_SIZE: usize = 100;
+// Larger imports have to be split up with narrower queries.
+const MAX_IMPORT_ISSUES: usize = 1000;
+
+// Creates a task in the project for every issue matching `jql` that no task of the
+// project is linked to yet. Tasks take the issue's summary, description and mapped
+// status, go to the user with the assignee's email, and are linked for syncing.
+pub async fn import_issues(
+    jira: &Jira,
+    pool: &DbPool,
+    project_id: Uuid,
+    jql: &str,
+) -> async_graphql::Result<JiraImportResult> {
+    let issues = fetch_all(jira, jql).await.map_err(|e| e.extend())?;
+
+    let keys: Vec<String> = issues.iter().map(|issue| issue.key.clone()).collect();
+    let linked: HashMap<String, Uuid> = sqlx::query!(
+        r#"SELECT jira_ticket_id AS "key!", id FROM tasks WHERE project_id = $1 AND jira_ticket_id = ANY($2)"#,
+        project_id,
+        &keys
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.key, row.id))
+    .collect();
+
+    let emails: Vec<String> = issues
+        .iter()
+        .filter_map(|issue| issue.fields.assignee.as_ref()?.email_address.as_ref())
+        .map(|email| email.to_lowercase())
+        .collect();
+    // Only project members may be assigned its tasks.
+    let users: HashMap<String, Uuid> = sqlx::query!(
+        "SELECT u.email, u.id FROM users u
+        JOIN project_members m ON m.user_id = u.id AND m.project_id = $1
+        WHERE u.email = ANY($2)",
+        project_id,
+        &emails
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.email, row.id))
+    .collect();
+
+    let mut result = JiraImportResult {
+        imported: 0,
+        skipped: 0,
+        failed: 0,
+        issues: Vec::new(),
+    };
+    for issue in issues {
+        if let Some(task_id) = linked.get(&issue.key) {
+            result.skipped += 1;
+            result.issues.push(JiraImportIssue {
+                issue_key: issue.key,
+                outcome: JiraImportOutcome::Skipped,
+                task: None,
+                notes: vec![format!("Already linked to task {}", task_id)],
+            });
+            continue;
+        }
+
+        let mut notes = Vec::new();
+        let status = match jira.statuses().task_status(&issue.fields.status.name) {
+            Some(status) => status,
+            None => {
+                notes.push(format!(
+                    "Jira status '{}' is not mapped, so the task starts as NotStarted",
+                    issue.fields.status.name
+                ));
+                TaskStatus::NotStarted
+            }
+        };
+        let assigned_to_id = match &issue.fields.assignee {
+            None => None,
+            Some(assignee) => match &assignee.email_address {
+                Some(email) => {
+                    let user_id = users.get(&email.to_lowercase()).copied();
+                    if user_id.is_none() {
+                        notes.push(format!(
+                            "No project member has the email {}, left unassigned",
+                            email
+                        ));
+                    }
+                    user_id
+                }
+                None => {
+                    notes.push(
+                        "Jira did not share the assignee's email, left unassigned".to_string(),
+                    );
+                    None
+                }
+            },
+        };
+
+        // One issue that cannot be stored, e.g. with an overlong summary, does not
+        // stop the others. The cause is logged rather than shown to the client.
+        match create_task(jira, pool, project_id, &issue, assigned_to_id, status).await {
+            Ok(task) => {
+                result.imported += 1;
+                result.issues.push(JiraImportIssue {
+                    issue_key: issue.key,
+                    outcome: JiraImportOutcome::Imported,
+                    task: Some(task),
+                    notes,
+                });
+            }
+            Err(e) => {
+                eprintln!("Failed to import Jira issue {}: {}", issue.key, e);
+                result.failed += 1;
+                result.issues.push(JiraImportIssue {
+                    issue_key: issue.key,
+                    outcome: JiraImportOutcome::Failed,
+                    task: None,
+                    notes: vec!["The task could not be created".to_string()],
+                });
+            }
+        }
+    }
+    Ok(result)
+}
+
+// Creates the task for an issue and starts tracking the issue, all or nothing.
+async fn create_task(
+    jira: &Jira,
+    pool: &DbPool,
+    project_id: Uuid,
+    issue: &JiraIssue,
+    assigned_to_id: Option<Uuid>,
+    status: TaskStatus,
+) -> Result<Task, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let task = sqlx::query_as!(
+        Task,
+        r#"
+        INSERT INTO tasks (title, description, project_id, assigned_to_id, status, jira_ticket_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+        "#,
+        issue.fields.summary,
+        issue.fields.description,
+        project_id,
+        assigned_to_id,
+        status as _,
+        issue.key
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    enqueue(&mut *tx, WebhookEvent::TaskCreated(&task)).await?;
+    jira.track(&mut *tx, task.id, issue).await?;
+    tx.commit().await?;
+    Ok(task)
+}
+
+// Pages through the search. Everything is fetched before any task is created, so an
+// oversized or failing query leaves the project untouched.
+async fn fetch_all(jira: &Jira, jql: &str) -> Result<Vec<JiraIssue>, JiraError> {
+    let too_many = JiraError::TooManyIssues {
+        limit: MAX_IMPORT_ISSUES,
+    };
+    let mut issues = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = jira
+            .client()
+            .search(jql, cursor.as_ref(), PAGE_SIZE)
+            .await?;
+        // Data Center says up front how many issues match; on Cloud it shows as pages arrive.
+        if page.total.is_some_and(|total| total > MAX_IMPORT_ISSUES) {
+            return Err(too_many);
+        }
+        issues.extend(page.issues);
+        if issues.len() > MAX_IMPORT_ISSUES {
+            return Err(too_many);
+        }
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => return Ok(issues),
+        }
+    }
+}
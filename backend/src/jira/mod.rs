@@ -0,0 +1,9 @@
+pub mod client;
+pub mod error;
+pub mod import;
+pub mod status_map;
+pub mod sync;
+pub mod webhook;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,62 @@
+use super::error::JiraError;
+use crate::models::task_status::TaskStatus;
+use std::{collections::HashMap, env};
+
+// Which Jira status each task status corresponds to. Task statuses without an
+// entry are not pushed, and Jira statuses without one are not pulled.
+#[derive(Clone)]
+pub struct StatusMap {
+    to_jira: HashMap<TaskStatus, String>,
+}
+
+impl StatusMap {
+    // Reads JIRA_STATUS_MAP, a JSON object such as {"InProgress": "In Progress"}.
+    pub fn from_env() -> Result<Self, JiraError> {
+        let Ok(value) = env::var("JIRA_STATUS_MAP") else {
+            return Ok(Self::default());
+        };
+        let to_jira: HashMap<TaskStatus, String> = serde_json::from_str(&value)
+            .map_err(|e| JiraError::Config(format!("JIRA_STATUS_MAP is invalid: {}", e)))?;
+        Self::new(to_jira)
+    }
+
+    // Each Jira status may only be used once, so pulling it back is unambiguous.
+    pub fn new(to_jira: HashMap<TaskStatus, String>) -> Result<Self, JiraError> {
+        let mut seen: Vec<&str> = Vec::new();
+        for name in to_jira.values() {
+            if seen.iter().any(|other| other.eq_ignore_ascii_case(name)) {
+                return Err(JiraError::Config(format!(
+                    "JIRA_STATUS_MAP maps several statuses to '{}'",
+                    name
+                )));
+            }
+            seen.push(name);
+        }
+        Ok(Self { to_jira })
+    }
+
+    pub fn jira_status(&self, status: TaskStatus) -> Option<&str> {
+        self.to_jira.get(&status).map(String::as_str)
+    }
+
+    // Jira status names are matched case-insensitively.
+    pub fn task_status(&self, jira_status: &str) -> Option<TaskStatus> {
+        self.to_jira
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(jira_status))
+            .map(|(status, _)| *status)
+    }
+}
+
+// The statuses of Jira's default workflow.
+impl Default for StatusMap {
+    fn default() -> Self {
+        Self {
+            to_jira: HashMap::from([
+                (TaskStatus::NotStarted, "To Do".to_string()),
+                (TaskStatus::InProgress, "In Progress".to_string()),
+                (TaskStatus::Completed, "Done".to_string()),
+            ]),
+        }
+    }
+}
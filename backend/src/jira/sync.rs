@@ -0,0 +1,290 @@
+use super::{
+    client::{JiraClient, JiraIssue, normalize_issue_key},
+    error::JiraError,
+    status_map::StatusMap,
+};
+use crate::{
+    db::DbPool,
+    events::{AppEvent, EventBus},
+    models::{
+        task::change_task_status,
+        task_status::{TaskStatus, TransitionRules},
+    },
+};
+use sqlx::{Executor, Postgres};
+use std::{collections::HashMap, env, error::Error, time::Duration};
+use uuid::Uuid;
+
+// How many linked issues one poll request asks Jira about.
+const POLL_BATCH_SIZE: usize = 50;
+
+// The Jira integration, registered as schema data only when it is configured.
+// Local status changes are pushed to the linked issue as workflow transitions, and
+// a poller pulls status changes made in Jira back into the tasks.
+#[derive(Clone)]
+pub struct Jira {
+    client: JiraClient,
+    statuses: StatusMap,
+    poll_interval: Option<Duration>,
+}
+
+impl Jira {
+    // See `JiraClient::from_env` and `StatusMap::from_env`; JIRA_POLL_SECS sets how
+    // often Jira is polled (default 300, 0 turns polling off).
+    pub fn from_env() -> Result<Option<Self>, JiraError> {
+        let Some(client) = JiraClient::from_env()? else {
+            return Ok(None);
+        };
+        let poll_secs: u64 = match env::var("JIRA_POLL_SECS") {
+            Ok(value) => value.parse().map_err(|_| {
+                JiraError::Config("JIRA_POLL_SECS must be a non-negative integer".to_string())
+            })?,
+            Err(_) => 300,
+        };
+        Ok(Some(Self::new(
+            client,
+            StatusMap::from_env()?,
+            (poll_secs > 0).then(|| Duration::from_secs(poll_secs)),
+        )))
+    }
+
+    pub(super) fn new(
+        client: JiraClient,
+        statuses: StatusMap,
+        poll_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            client,
+            statuses,
+            poll_interval,
+        }
+    }
+
+    pub fn client(&self) -> &JiraClient {
+        &self.client
+    }
+
+    pub fn statuses(&self) -> &StatusMap {
+        &self.statuses
+    }
+
+    // Starts tracking a newly linked issue from its current Jira status.
+    pub async fn track<'c, E>(
+        &self,
+        executor: E,
+        task_id: Uuid,
+        issue: &JiraIssue,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'c, Database = Postgres>,
+    {
+        record_sync(
+            executor,
+            task_id,
+            &issue.key,
+            &issue.fields.status.name,
+            None,
+        )
+        .await
+    }
+
+    // Pushes a local status change to the linked issue in the background. The
+    // outcome shows up in the task's sync state rather than failing the change.
+    pub fn push_status(&self, pool: DbPool, task_id: Uuid, key: String, status: TaskStatus) {
+        let Some(target) = self.statuses.jira_status(status).map(str::to_string) else {
+            return;
+        };
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            let recorded = match client.transition_to(&key, &target).await {
+                Ok(()) => record_sync(&pool, task_id, &key, &target, None).await,
+                Err(e) => record_error(&pool, task_id, &key, &e.to_string()).await,
+            };
+            if let Err(e) = recorded {
+                eprintln!("Failed to record Jira sync of task {}: {}", task_id, e);
+            }
+        });
+    }
+
+    // Polls Jira every JIRA_POLL_SECS until the server stops.
+    pub async fn run_poller(self, pool: DbPool, rules: TransitionRules, bus: EventBus) {
+        let Some(interval) = self.poll_interval else {
+            return;
+        };
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.poll(&pool, &rules, &bus).await {
+                eprintln!("Jira poll failed: {}", e);
+            }
+        }
+    }
+
+    // Pulls the status of every linked issue. A Jira status that changed since the
+    // last sync is applied to the task like any other status change.
+    pub(super) async fn poll(
+        &self,
+        pool: &DbPool,
+        rules: &TransitionRules,
+        bus: &EventBus,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let linked = sqlx::query!(
+            r#"
+            SELECT t.id AS "id!", t.project_id AS "project_id!", t.status AS "status!: TaskStatus",
+                t.jira_ticket_id AS "issue_key!",
+                s.jira_status AS "jira_status?"
+            FROM tasks t
+            LEFT JOIN task_jira_sync s ON s.task_id = t.id AND s.issue_key = t.jira_ticket_id
+            WHERE t.jira_ticket_id IS NOT NULL
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        // Keys stored before links were validated may not be usable in JQL.
+        let mut valid = Vec::new();
+        for task in linked {
+            match normalize_issue_key(&task.issue_key) {
+                Ok(key) if key == task.issue_key => valid.push(task),
+                _ => {
+                    let error = JiraError::InvalidKey(task.issue_key.clone()).to_string();
+                    record_error(pool, task.id, &task.issue_key, &error).await?;
+                }
+            }
+        }
+
+        for batch in valid.chunks(POLL_BATCH_SIZE) {
+            let keys: Vec<String> = batch
+                .iter()
+                .map(|task| format!("\"{}\"", task.issue_key))
+                .collect();
+            let jql = format!("key in ({})", keys.join(", "));
+            // A failed search only holds back its own batch.
+            let found: HashMap<String, String> =
+                match self.client.search(&jql, None, batch.len()).await {
+                    Ok(page) => page
+                        .issues
+                        .into_iter()
+                        .map(|issue| (issue.key, issue.fields.status.name))
+                        .collect(),
+                    Err(e) => {
+                        eprintln!("Jira poll of {} issues failed: {}", batch.len(), e);
+                        let error = e.to_string();
+                        for task in batch {
+                            record_error(pool, task.id, &task.issue_key, &error).await?;
+                        }
+                        continue;
+                    }
+                };
+
+            for task in batch {
+                let Some(jira_status) = found.get(&task.issue_key) else {
+                    let error = JiraError::IssueNotFound(task.issue_key.clone()).to_string();
+                    record_error(pool, task.id, &task.issue_key, &error).await?;
+                    continue;
+                };
+                let unchanged = task
+                    .jira_status
+                    .as_deref()
+                    .is_some_and(|seen| seen.eq_ignore_ascii_case(jira_status));
+                if unchanged {
+                    // Keeps the error of a failed push, which still needs attention.
+                    sqlx::query!(
+                        "UPDATE task_jira_sync SET synced_at = NOW() WHERE task_id = $1",
+                        task.id
+                    )
+                    .execute(pool)
+                    .await?;
+                    continue;
+                }
+                // A newly tracked issue only records its status.
+                let pulled = self
+                    .statuses
+                    .task_status(jira_status)
+                    .filter(|status| task.jira_status.is_some() && *status != task.status);
+
+                let mut error = None;
+                if let Some(status) = pulled {
+                    let comment = format!("Synced from Jira issue {}", task.issue_key);
+                    match change_task_status(pool, rules, task.id, status, Some(comment), None)
+                        .await
+                    {
+                        Ok(_) => {
+                            bus.publish(AppEvent::TaskUpdated {
+                                project_id: task.project_id,
+                                task_id: task.id,
+                            })
+                            .await;
+                        }
+                        Err(e) => {
+                            error = Some(format!(
+                                "Could not apply Jira status '{}': {}",
+                                jira_status, e.message
+                            ));
+                        }
+                    }
+                }
+                // A rejected status is recorded as seen too, so it is not retried on every poll.
+                record_sync(
+                    pool,
+                    task.id,
+                    &task.issue_key,
+                    jira_status,
+                    error.as_deref(),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) async fn record_sync<'c, E>(
+    executor: E,
+    task_id: Uuid,
+    key: &str,
+    jira_status: &str,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO task_jira_sync (task_id, issue_key, jira_status, synced_at, last_error)
+        VALUES ($1, $2, $3, NOW(), $4)
+        ON CONFLICT (task_id) DO UPDATE
+        SET issue_key = EXCLUDED.issue_key, jira_status = EXCLUDED.jira_status,
+            synced_at = EXCLUDED.synced_at, last_error = EXCLUDED.last_error",
+        task_id,
+        key,
+        jira_status,
+        error
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+// Keeps the last seen status unless the task has been linked to another issue since.
+async fn record_error(
+    pool: &DbPool,
+    task_id: Uuid,
+    key: &str,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO task_jira_sync (task_id, issue_key, last_error) VALUES ($1, $2, $3)
+        ON CONFLICT (task_id) DO UPDATE
+        SET last_error = EXCLUDED.last_error,
+            jira_status = CASE WHEN task_jira_sync.issue_key = EXCLUDED.issue_key
+                THEN task_jira_sync.jira_status END,
+            issue_key = EXCLUDED.issue_key",
+        task_id,
+        key,
+        error
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
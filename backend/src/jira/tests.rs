@@ -0,0 +1,538 @@
+// Runs the integration against a stub Jira served on a local port. Tests that touch
+// the database get a fresh one with every migration applied.
+use super::{
+    client::{Auth, Deployment, JiraClient, PageCursor},
+    status_map::StatusMap,
+    sync::Jira,
+};
+use crate::{
+    auth::AuthUser,
+    db::DbPool,
+    events::{AppEvent, EventBus},
+    graphql::{
+        loaders::Loaders,
+        schema::{AppSchema, MutationRoot, QueryRoot},
+        subscription::SubscriptionRoot,
+    },
+    models::task_status::{TaskStatus, TransitionRules},
+};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, http::header::AUTHORIZATION, web};
+use async_graphql::{Request, Response, Schema, Variables};
+use chrono::Utc;
+use serde_json::{Value, json};
+use std::{collections::HashMap, net::TcpListener, sync::Mutex, time::Duration};
+use uuid::Uuid;
+
+const TOKEN: &str = "stub-token";
+
+// The statuses of Jira's default workflow each status can move to.
+fn workflow(from: &str) -> &'static [&'static str] {
+    match from {
+        "To Do" => &["In Progress"],
+        "In Progress" => &["To Do", "Done"],
+        "Done" => &["To Do"],
+        _ => &[],
+    }
+}
+
+// The issues the stub knows, as their current status by key.
+struct StubJira {
+    issues: Mutex<HashMap<String, String>>,
+}
+
+impl StubJira {
+    fn status(&self, key: &str) -> Option<String> {
+        self.issues.lock().unwrap().get(key).cloned()
+    }
+
+    fn set_status(&self, key: &str, status: &str) {
+        self.issues
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), status.to_string());
+    }
+
+    // The known issues among those named by a `key in (...)` query, in query order.
+    fn matching(&self, jql: &str) -> Vec<(String, String)> {
+        let keys = jql
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .map_or("", |(keys, _)| keys);
+        keys.split(',')
+            .map(|key| key.trim().trim_matches('"'))
+            .filter_map(|key| Some((key.to_string(), self.status(key)?)))
+            .collect()
+    }
+}
+
+// v2 describes issues in wiki markup, v3 in Atlassian Document Format.
+fn issue_json(key: &str, status: &str, adf: bool) -> Value {
+    let description = if adf {
+        json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        { "type": "text", "text": "First " },
+                        { "type": "text", "text": "line", "marks": [{ "type": "strong" }] },
+                    ],
+                },
+                {
+                    "type": "paragraph",
+                    "content": [{ "type": "text", "text": "Second line" }],
+                },
+            ],
+        })
+    } else {
+        json!("First line\nSecond line")
+    };
+    json!({
+        "key": key,
+        "fields": {
+            "summary": format!("Summary of {}", key),
+            "description": description,
+            "status": { "name": status },
+            "assignee": null,
+        },
+    })
+}
+
+fn authorized(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(AUTHORIZATION)
+        .is_some_and(|value| value.as_bytes() == format!("Bearer {}", TOKEN).as_bytes())
+}
+
+fn issue_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(json!({ "errorMessages": ["Issue does not exist"] }))
+}
+
+async fn get_issue(
+    req: HttpRequest,
+    stub: web::Data<StubJira>,
+    key: web::Path<String>,
+) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    match stub.status(&key) {
+        Some(status) => HttpResponse::Ok().json(issue_json(&key, &status, false)),
+        None => issue_not_found(),
+    }
+}
+
+async fn get_transitions(
+    req: HttpRequest,
+    stub: web::Data<StubJira>,
+    key: web::Path<String>,
+) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let Some(status) = stub.status(&key) else {
+        return issue_not_found();
+    };
+    let transitions: Vec<Value> = workflow(&status)
+        .iter()
+        .enumerate()
+        .map(|(i, to)| json!({ "id": (i + 11).to_string(), "to": { "name": to } }))
+        .collect();
+    HttpResponse::Ok().json(json!({ "transitions": transitions }))
+}
+
+async fn post_transition(
+    req: HttpRequest,
+    stub: web::Data<StubJira>,
+    key: web::Path<String>,
+    body: web::Json<Value>,
+) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let Some(status) = stub.status(&key) else {
+        return issue_not_found();
+    };
+    let target = body["transition"]["id"]
+        .as_str()
+        .and_then(|id| id.parse::<usize>().ok())
+        .and_then(|id| workflow(&status).get(id.checked_sub(11)?));
+    match target {
+        Some(to) => {
+            stub.set_status(&key, to);
+            HttpResponse::NoContent().finish()
+        }
+        None => HttpResponse::BadRequest().finish(),
+    }
+}
+
+// Data Center: offset paging with a total.
+async fn search_v2(
+    req: HttpRequest,
+    stub: web::Data<StubJira>,
+    body: web::Json<Value>,
+) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let found = stub.matching(body["jql"].as_str().unwrap_or_default());
+    let start_at = body["startAt"].as_u64().unwrap_or(0) as usize;
+    let max_results = body["maxResults"].as_u64().unwrap_or(50) as usize;
+    let issues: Vec<Value> = found
+        .iter()
+        .skip(start_at)
+        .take(max_results)
+        .map(|(key, status)| issue_json(key, status, false))
+        .collect();
+    HttpResponse::Ok().json(json!({
+        "startAt": start_at,
+        "maxResults": max_results,
+        "total": found.len(),
+        "issues": issues,
+    }))
+}
+
+// Cloud: token paging without a total.
+async fn search_v3(
+    req: HttpRequest,
+    stub: web::Data<StubJira>,
+    body: web::Json<Value>,
+) -> HttpResponse {
+    if !authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let found = stub.matching(body["jql"].as_str().unwrap_or_default());
+    let start = body["nextPageToken"]
+        .as_str()
+        .and_then(|token| token.parse::<usize>().ok())
+        .unwrap_or(0);
+    let max_results = body["maxResults"].as_u64().unwrap_or(50) as usize;
+    let issues: Vec<Value> = found
+        .iter()
+        .skip(start)
+        .take(max_results)
+        .map(|(key, status)| issue_json(key, status, true))
+        .collect();
+    let end = start + issues.len();
+    let mut page = json!({ "issues": issues, "isLast": end >= found.len() });
+    if end < found.len() {
+        page["nextPageToken"] = json!(end.to_string());
+    }
+    HttpResponse::Ok().json(page)
+}
+
+// Serves a stub Jira with the given issues and returns its base URL.
+fn start_stub(issues: &[(&str, &str)]) -> (String, web::Data<StubJira>) {
+    let stub = web::Data::new(StubJira {
+        issues: Mutex::new(
+            issues
+                .iter()
+                .map(|(key, status)| (key.to_string(), status.to_string()))
+                .collect(),
+        ),
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let data = stub.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/rest/api/2/issue/{key}", web::get().to(get_issue))
+            .route(
+                "/rest/api/2/issue/{key}/transitions",
+                web::get().to(get_transitions),
+            )
+            .route(
+                "/rest/api/2/issue/{key}/transitions",
+                web::post().to(post_transition),
+            )
+            .route("/rest/api/2/search", web::post().to(search_v2))
+            .route("/rest/api/3/search/jql", web::post().to(search_v3))
+    })
+    .workers(1)
+    .disable_signals()
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+    (base_url, stub)
+}
+
+fn jira(base_url: &str, deployment: Deployment) -> Jira {
+    let client = JiraClient::new(
+        base_url,
+        Auth::Bearer(TOKEN.to_string()),
+        deployment,
+        Duration::from_secs(5),
+    )
+    .unwrap();
+    Jira::new(client, StatusMap::default(), None)
+}
+
+// A user owning a project with one task. Returns the user and task ids.
+async fn seed(pool: &DbPool) -> (Uuid, Uuid) {
+    let user_id = sqlx::query_scalar!(
+        "INSERT INTO users (email, password_hash) VALUES ('owner@example.com', '-') RETURNING id"
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    let project_id = sqlx::query_scalar!(
+        "INSERT INTO projects (name, owner_id) VALUES ('Stubbed', $1) RETURNING id",
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, 'Owner')",
+        project_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    let task_id = add_task(pool, project_id, None).await;
+    (user_id, task_id)
+}
+
+// A task linked to `key`, tracked from `jira_status` when given.
+async fn add_linked_task(pool: &DbPool, key: &str, jira_status: Option<&str>) -> Uuid {
+    let project_id = sqlx::query_scalar!("SELECT id FROM projects LIMIT 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    let task_id = add_task(pool, project_id, Some(key)).await;
+    if let Some(jira_status) = jira_status {
+        sqlx::query!(
+            "INSERT INTO task_jira_sync (task_id, issue_key, jira_status, synced_at) VALUES ($1, $2, $3, NOW())",
+            task_id,
+            key,
+            jira_status
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    task_id
+}
+
+async fn add_task(pool: &DbPool, project_id: Uuid, key: Option<&str>) -> Uuid {
+    sqlx::query_scalar!(
+        "INSERT INTO tasks (title, project_id, jira_ticket_id) VALUES ('Task', $1, $2) RETURNING id",
+        project_id,
+        key
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[derive(Debug, PartialEq)]
+struct SyncState {
+    jira_status: Option<String>,
+    last_error: Option<String>,
+}
+
+async fn sync_state(pool: &DbPool, task_id: Uuid) -> Option<SyncState> {
+    sqlx::query_as!(
+        SyncState,
+        "SELECT jira_status, last_error FROM task_jira_sync WHERE task_id = $1",
+        task_id
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap()
+}
+
+// Pushes run in the background; waits until one has recorded its outcome.
+async fn wait_for_push(pool: &DbPool, task_id: Uuid, before: &SyncState) -> SyncState {
+    for _ in 0..50 {
+        let state = sync_state(pool, task_id).await.unwrap();
+        if state != *before {
+            return state;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("the push to Jira was never recorded");
+}
+
+fn schema(pool: &DbPool, jira: Jira) -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(pool.clone())
+        .data(TransitionRules::default())
+        .data(EventBus::from_env(pool))
+        .data(jira)
+        .data(Loaders::new(pool))
+        .finish()
+}
+
+async fn link(schema: &AppSchema, user_id: Uuid, task_id: Uuid, key: &str) -> Response {
+    let request = Request::new(
+        "mutation($taskId: ID!, $key: String!) {
+            linkJiraTicket(taskId: $taskId, jiraTicketId: $key) { jiraTicketId }
+        }",
+    )
+    .variables(Variables::from_json(
+        json!({ "taskId": task_id, "key": key }),
+    ))
+    .data(AuthUser {
+        id: user_id,
+        expires_at: Utc::now() + chrono::Duration::hours(1),
+    });
+    schema.execute(request).await
+}
+
+fn error_code(response: &Response) -> Option<String> {
+    let code = response.errors.first()?.extensions.as_ref()?.get("code")?;
+    match code {
+        async_graphql::Value::String(code) => Some(code.clone()),
+        _ => None,
+    }
+}
+
+#[sqlx::test]
+async fn linking_checks_the_issue_in_jira(pool: DbPool) {
+    let (base_url, _stub) = start_stub(&[("ABC-1", "To Do")]);
+    let schema = schema(&pool, jira(&base_url, Deployment::DataCenter));
+    let (user_id, task_id) = seed(&pool).await;
+
+    let response = link(&schema, user_id, task_id, "not a key").await;
+    assert_eq!(error_code(&response).as_deref(), Some("JIRA_INVALID_KEY"));
+
+    let response = link(&schema, user_id, task_id, "abc-404").await;
+    assert_eq!(
+        error_code(&response).as_deref(),
+        Some("JIRA_ISSUE_NOT_FOUND")
+    );
+    assert_eq!(sync_state(&pool, task_id).await, None);
+
+    let response = link(&schema, user_id, task_id, "abc-1").await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["linkJiraTicket"]["jiraTicketId"], "ABC-1");
+    assert_eq!(
+        sync_state(&pool, task_id).await,
+        Some(SyncState {
+            jira_status: Some("To Do".to_string()),
+            last_error: None,
+        })
+    );
+}
+
+#[sqlx::test]
+async fn status_changes_are_pushed_as_transitions(pool: DbPool) {
+    let (base_url, stub) = start_stub(&[("ABC-1", "To Do"), ("ABC-2", "Done")]);
+    let jira = jira(&base_url, Deployment::DataCenter);
+    seed(&pool).await;
+
+    let task_id = add_linked_task(&pool, "ABC-1", Some("To Do")).await;
+    let before = sync_state(&pool, task_id).await.unwrap();
+    jira.push_status(
+        pool.clone(),
+        task_id,
+        "ABC-1".to_string(),
+        TaskStatus::InProgress,
+    );
+    let after = wait_for_push(&pool, task_id, &before).await;
+    assert_eq!(stub.status("ABC-1").as_deref(), Some("In Progress"));
+    assert_eq!(
+        after,
+        SyncState {
+            jira_status: Some("In Progress".to_string()),
+            last_error: None,
+        }
+    );
+
+    // Done has no transition into In Progress, so the push fails and says why.
+    let task_id = add_linked_task(&pool, "ABC-2", Some("Done")).await;
+    let before = sync_state(&pool, task_id).await.unwrap();
+    jira.push_status(
+        pool.clone(),
+        task_id,
+        "ABC-2".to_string(),
+        TaskStatus::InProgress,
+    );
+    let after = wait_for_push(&pool, task_id, &before).await;
+    assert_eq!(stub.status("ABC-2").as_deref(), Some("Done"));
+    assert_eq!(after.jira_status.as_deref(), Some("Done"));
+    assert!(after.last_error.unwrap().contains("no transition"));
+}
+
+#[sqlx::test]
+async fn polling_pulls_status_changes_from_jira(pool: DbPool) {
+    let (base_url, stub) = start_stub(&[("ABC-1", "To Do")]);
+    let jira = jira(&base_url, Deployment::DataCenter);
+    seed(&pool).await;
+    let task_id = add_linked_task(&pool, "ABC-1", Some("To Do")).await;
+    let missing_id = add_linked_task(&pool, "ABC-9", None).await;
+
+    let bus = EventBus::from_env(&pool);
+    let mut events = bus.subscribe();
+    stub.set_status("ABC-1", "In Progress");
+    jira.poll(&pool, &TransitionRules::default(), &bus)
+        .await
+        .unwrap();
+
+    let status = sqlx::query_scalar!(
+        r#"SELECT status AS "status: TaskStatus" FROM tasks WHERE id = $1"#,
+        task_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(status, TaskStatus::InProgress);
+    let comment = sqlx::query_scalar!(
+        "SELECT comment FROM task_status_history WHERE task_id = $1",
+        task_id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(comment.as_deref(), Some("Synced from Jira issue ABC-1"));
+    assert_eq!(
+        sync_state(&pool, task_id).await,
+        Some(SyncState {
+            jira_status: Some("In Progress".to_string()),
+            last_error: None,
+        })
+    );
+    assert!(matches!(
+        events.try_recv(),
+        Ok(AppEvent::TaskUpdated { task_id: updated, .. }) if updated == task_id
+    ));
+
+    let missing = sync_state(&pool, missing_id).await.unwrap();
+    assert!(missing.last_error.unwrap().contains("not found"));
+}
+
+#[tokio::test]
+async fn cloud_searches_page_by_token_and_flatten_descriptions() {
+    let (base_url, _stub) =
+        start_stub(&[("ABC-1", "To Do"), ("ABC-2", "To Do"), ("ABC-3", "Done")]);
+    let jql = "key in (\"ABC-1\", \"ABC-2\", \"ABC-3\")";
+
+    let client = jira(&base_url, Deployment::Cloud).client().clone();
+    let first = client.search(jql, None, 2).await.unwrap();
+    assert_eq!(first.issues.len(), 2);
+    assert_eq!(first.total, None);
+    assert_eq!(
+        first.issues[0].fields.description.as_deref(),
+        Some("First line\nSecond line")
+    );
+    let next = first.next.expect("a second page");
+    let second = client.search(jql, Some(&next), 2).await.unwrap();
+    assert_eq!(second.issues[0].key, "ABC-3");
+    assert_eq!(second.next, None);
+
+    let client = jira(&base_url, Deployment::DataCenter).client().clone();
+    let first = client.search(jql, None, 2).await.unwrap();
+    assert_eq!(first.total, Some(3));
+    assert_eq!(first.next, Some(PageCursor::Offset(2)));
+    assert_eq!(
+        first.issues[0].fields.description.as_deref(),
+        Some("First line\nSecond line")
+    );
+}
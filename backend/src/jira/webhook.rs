@@ -0,0 +1,384 @@
+use super::{
+    client::{JiraStatus, JiraUser},
+    error::JiraError,
+    status_map::StatusMap,
+    sync::record_sync,
+};
+use crate::{
+    db::DbPool,
+    events::{AppEvent, EventBus},
+    models::{
+        task::{UpdateTaskInput, change_task_status, update_task_fields},
+        task_status::{TaskStatus, TransitionRules},
+    },
+};
+use actix_web::{HttpRequest, HttpResponse, web};
+use async_graphql::{ID, MaybeUndefined};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::env;
+use uuid::Uuid;
+
+// Receives Jira's issue webhooks at /webhooks/jira and applies the changes to the
+// linked tasks. Jira Cloud signs the body with the shared secret (X-Hub-Signature).
+// Data Center cannot sign, so there the secret can go into the URL as `?secret=`;
+// that ends up in access logs, so it must be enabled explicitly.
+pub struct JiraWebhook {
+    secret: String,
+    allow_query_secret: bool,
+    statuses: StatusMap,
+    pool: DbPool,
+    rules: TransitionRules,
+    bus: EventBus,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookEvent {
+    webhook_event: String,
+    user: Option<JiraUser>,
+    issue: Option<WebhookIssue>,
+    changelog: Option<Changelog>,
+}
+
+#[derive(Deserialize)]
+struct WebhookIssue {
+    key: String,
+    fields: WebhookFields,
+}
+
+#[derive(Deserialize)]
+struct WebhookFields {
+    summary: Option<String>,
+    status: Option<JiraStatus>,
+    assignee: Option<JiraUser>,
+}
+
+#[derive(Deserialize)]
+struct Changelog {
+    items: Vec<ChangeItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangeItem {
+    field: String,
+    from_string: Option<String>,
+    to_string: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SecretQuery {
+    secret: Option<String>,
+}
+
+// A task linked to the issue an event is about.
+struct LinkedTask {
+    id: Uuid,
+    project_id: Uuid,
+    title: String,
+    status: TaskStatus,
+}
+
+// One field of a task changed from Jira, as written to `jira_changes`.
+struct Change {
+    field: &'static str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    error: Option<String>,
+}
+
+impl Change {
+    fn from_item(field: &'static str, item: &ChangeItem) -> Self {
+        Self {
+            field,
+            old_value: item.from_string.clone(),
+            new_value: item.to_string.clone(),
+            error: None,
+        }
+    }
+}
+
+impl JiraWebhook {
+    // Reads JIRA_WEBHOOK_SECRET, JIRA_WEBHOOK_ALLOW_QUERY_SECRET ("true" accepts
+    // `?secret=` from unsigned requests) and JIRA_STATUS_MAP. Returns None when no
+    // secret is set, which leaves the endpoint off.
+    pub fn from_env(
+        pool: DbPool,
+        rules: TransitionRules,
+        bus: EventBus,
+    ) -> Result<Option<Self>, JiraError> {
+        let Ok(secret) = env::var("JIRA_WEBHOOK_SECRET") else {
+            return Ok(None);
+        };
+        if secret.is_empty() {
+            return Err(JiraError::Config(
+                "JIRA_WEBHOOK_SECRET must not be empty".to_string(),
+            ));
+        }
+        Ok(Some(Self {
+            secret,
+            allow_query_secret: env::var("JIRA_WEBHOOK_ALLOW_QUERY_SECRET")
+                .is_ok_and(|value| value == "true"),
+            statuses: StatusMap::from_env()?,
+            pool,
+            rules,
+            bus,
+        }))
+    }
+
+    fn verify(&self, req: &HttpRequest, body: &[u8]) -> bool {
+        if let Some(signature) = req.headers().get("X-Hub-Signature") {
+            let Some(expected) = signature
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix("sha256="))
+                .and_then(|value| hex::decode(value).ok())
+            else {
+                return false;
+            };
+            let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(body);
+            return mac.verify_slice(&expected).is_ok();
+        }
+        if !self.allow_query_secret {
+            return false;
+        }
+        web::Query::<SecretQuery>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().secret)
+            .is_some_and(|secret| constant_time_eq(secret.as_bytes(), self.secret.as_bytes()))
+    }
+
+    // Applies the event to every task linked to its issue and returns how many
+    // changes were applied.
+    async fn handle(&self, event: WebhookEvent) -> Result<usize, sqlx::Error> {
+        let Some(issue) = &event.issue else {
+            return Ok(0);
+        };
+        let tasks = sqlx::query_as!(
+            LinkedTask,
+            r#"SELECT id, project_id, title, status AS "status: TaskStatus" FROM tasks WHERE jira_ticket_id = $1"#,
+            issue.key
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        if tasks.is_empty() {
+            return Ok(0);
+        }
+
+        let jira_user = event.user.as_ref().and_then(|user| {
+            user.display_name
+                .clone()
+                .or_else(|| user.email_address.clone())
+        });
+        // Status changes are credited to the Clarika user with the same email, if any.
+        let changed_by = match event
+            .user
+            .as_ref()
+            .and_then(|user| user.email_address.as_ref())
+        {
+            Some(email) => {
+                sqlx::query_scalar!(
+                    "SELECT id FROM users WHERE email = $1",
+                    email.to_lowercase()
+                )
+                .fetch_optional(&self.pool)
+                .await?
+            }
+            None => None,
+        };
+
+        let mut applied = 0;
+        for task in tasks {
+            let changes = match event.webhook_event.as_str() {
+                "jira:issue_deleted" => vec![self.unlink(task.id, &issue.key).await?],
+                "jira:issue_updated" => {
+                    let items = event
+                        .changelog
+                        .as_ref()
+                        .map_or(&[][..], |changelog| &changelog.items);
+                    self.apply_update(&task, issue, items, changed_by).await?
+                }
+                _ => Vec::new(),
+            };
+
+            let mut task_applied = 0;
+            for change in changes {
+                if change.error.is_none() {
+                    task_applied += 1;
+                }
+                sqlx::query!(
+                    "INSERT INTO jira_changes (task_id, issue_key, field, old_value, new_value, jira_user, error)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                    task.id,
+                    issue.key,
+                    change.field,
+                    change.old_value,
+                    change.new_value,
+                    jira_user,
+                    change.error
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            if task_applied > 0 {
+                self.bus
+                    .publish(AppEvent::TaskUpdated {
+                        project_id: task.project_id,
+                        task_id: task.id,
+                    })
+                    .await;
+            }
+            applied += task_applied;
+        }
+        Ok(applied)
+    }
+
+    // Takes the fields named in the changelog from the issue, the same way
+    // `update_task_status` and `update_task` would.
+    async fn apply_update(
+        &self,
+        task: &LinkedTask,
+        issue: &WebhookIssue,
+        items: &[ChangeItem],
+        changed_by: Option<Uuid>,
+    ) -> Result<Vec<Change>, sqlx::Error> {
+        let item = |field: &str| items.iter().find(|item| item.field == field);
+        let mut changes = Vec::new();
+
+        if let (Some(item), Some(jira_status)) = (item("status"), &issue.fields.status) {
+            // The poller must not apply this status a second time.
+            record_sync(&self.pool, task.id, &issue.key, &jira_status.name, None).await?;
+            let mut change = Change::from_item("status", item);
+            match self.statuses.task_status(&jira_status.name) {
+                Some(status) if status == task.status => {}
+                Some(status) => {
+                    let comment = format!("Changed in Jira issue {}", issue.key);
+                    let result = change_task_status(
+                        &self.pool,
+                        &self.rules,
+                        task.id,
+                        status,
+                        Some(comment),
+                        changed_by,
+                    )
+                    .await;
+                    change.error = result.err().map(|e| e.message);
+                    changes.push(change);
+                }
+                None => {
+                    change.error = Some(format!(
+                        "Jira status '{}' is not mapped to a task status",
+                        jira_status.name
+                    ));
+                    changes.push(change);
+                }
+            }
+        }
+
+        let mut input = UpdateTaskInput::default();
+        let mut field_changes = Vec::new();
+        if let (Some(item), Some(summary)) = (item("summary"), &issue.fields.summary)
+            && *summary != task.title
+        {
+            input.title = Some(summary.clone());
+            field_changes.push(Change::from_item("title", item));
+        }
+        if let Some(item) = item("assignee") {
+            let mut change = Change::from_item("assignee", item);
+            match &issue.fields.assignee {
+                None => input.assigned_to_id = MaybeUndefined::Null,
+                Some(JiraUser {
+                    email_address: Some(email),
+                    ..
+                }) => {
+                    // Only project members may be assigned its tasks.
+                    let user_id = sqlx::query_scalar!(
+                        "SELECT u.id FROM users u
+                        JOIN project_members m ON m.user_id = u.id AND m.project_id = $2
+                        WHERE u.email = $1",
+                        email.to_lowercase(),
+                        task.project_id
+                    )
+                    .fetch_optional(&self.pool)
+                    .await?;
+                    match user_id {
+                        Some(id) => {
+                            input.assigned_to_id = MaybeUndefined::Value(ID(id.to_string()))
+                        }
+                        None => {
+                            change.error =
+                                Some(format!("No project member has the email {}", email))
+                        }
+                    }
+                }
+                Some(_) => {
+                    change.error = Some("Jira did not share the assignee's email".to_string());
+                }
+            }
+            field_changes.push(change);
+        }
+
+        let has_updates = input.title.is_some() || !input.assigned_to_id.is_undefined();
+        if has_updates && let Err(e) = update_task_fields(&self.pool, task.id, input).await {
+            for change in field_changes.iter_mut().filter(|c| c.error.is_none()) {
+                change.error = Some(e.message.clone());
+            }
+        }
+        changes.extend(field_changes);
+        Ok(changes)
+    }
+
+    async fn unlink(&self, task_id: Uuid, key: &str) -> Result<Change, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET jira_ticket_id = NULL WHERE id = $1",
+            task_id
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query!("DELETE FROM task_jira_sync WHERE task_id = $1", task_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(Change {
+            field: "link",
+            old_value: Some(key.to_string()),
+            new_value: None,
+            error: None,
+        })
+    }
+}
+
+pub async fn jira_webhook(
+    webhook: Option<web::Data<JiraWebhook>>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    let Some(webhook) = webhook else {
+        return HttpResponse::NotFound().finish();
+    };
+    if !webhook.verify(&req, &body) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let event: WebhookEvent = match serde_json::from_slice(&body) {
+        Ok(event) => event,
+        Err(e) => {
+            return HttpResponse::BadRequest().body(format!("Invalid webhook payload: {}", e));
+        }
+    };
+    // Jira retries deliveries that fail, so only our own errors are reported as such.
+    match webhook.handle(event).await {
+        Ok(applied) => HttpResponse::Ok().json(json!({ "applied": applied })),
+        Err(e) => {
+            eprintln!("Failed to handle Jira webhook: {}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -1,20 +1,81 @@
 use actix_cors::Cors;
 use actix_files::NamedFile;
-use actix_web::{App, HttpRequest, HttpServer, guard, web};
-use async_graphql::{EmptySubscription, Schema};
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, guard, web};
+use async_graphql::{Data, Schema};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use std::env;
 
 mod ai;
+mod auth;
 mod db;
+mod events;
 mod graphql;
+mod jira;
 mod models;
+mod webhooks;
 
+use ai::{ledger::AiQuotaConfig, provider::provider_from_env, rate_limit::AiRateLimiter};
+use auth::{AuthUser, JwtKeys};
 use db::create_pool;
-use graphql::schema::{AppSchema, MutationRoot, QueryRoot};
+use events::EventBus;
+use graphql::{
+    loaders::Loaders,
+    schema::{AppSchema, MutationRoot, QueryRoot},
+    subscription::SubscriptionRoot,
+};
+use jira::{
+    sync::Jira,
+    webhook::{JiraWebhook, jira_webhook},
+};
+use models::task_status::TransitionRules;
+use webhooks::{address::AddressPolicy, delivery::DeliveryWorker};
 
-async fn index(schema: web::Data<AppSchema>, req: GraphQLRequest) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
+async fn index(
+    schema: web::Data<AppSchema>,
+    user: Option<AuthUser>,
+    req: GraphQLRequest,
+) -> GraphQLResponse {
+    let mut request = req.into_inner();
+    // Make the caller available to resolvers; anonymous requests simply carry no user.
+    if let Some(user) = user {
+        request = request.data(user);
+    }
+    schema.execute(request).await.into()
+}
+
+// Serves subscriptions over graphql-ws. Browsers cannot set headers on websockets,
+// so the token may also come as `Authorization` in the connection_init payload.
+async fn index_ws(
+    schema: web::Data<AppSchema>,
+    keys: web::Data<JwtKeys>,
+    user: Option<AuthUser>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    let mut data = Data::default();
+    if let Some(user) = user {
+        data.insert(user);
+    }
+
+    let keys = keys.into_inner();
+    GraphQLSubscription::new(AppSchema::clone(&schema))
+        .with_data(data)
+        .on_connection_init(move |payload| async move {
+            let mut data = Data::default();
+            let token = payload
+                .get("Authorization")
+                .or_else(|| payload.get("authorization"))
+                .and_then(|value| value.as_str())
+                .map(|value| value.strip_prefix("Bearer ").unwrap_or(value));
+            if let Some(token) = token {
+                let user = keys
+                    .verify_token(token)
+                    .map_err(|_| async_graphql::Error::new("Invalid token"))?;
+                data.insert(user);
+            }
+            Ok(data)
+        })
+        .start(&req, payload)
 }
 
 async fn index_graphiql(_req: HttpRequest) -> actix_web::Result<NamedFile> {
@@ -29,9 +90,50 @@ async fn main() -> std::io::Result<()> {
 
     let allowed_origin = env::var("ALLOWED_ORIGIN").expect("Allowed Origin not set");
 
-    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+    let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+    let jwt_keys = JwtKeys::new(&jwt_secret);
+
+    let ai_provider = provider_from_env().expect("Failed to configure AI provider");
+    let ai_rate_limiter = AiRateLimiter::from_env().expect("Failed to configure AI rate limits");
+    let ai_quotas = AiQuotaConfig::from_env().expect("Failed to configure AI quotas");
+
+    let event_bus = EventBus::from_env(&pool);
+    let transition_rules =
+        TransitionRules::from_env().expect("Failed to configure task status transitions");
+
+    let jira = Jira::from_env().expect("Failed to configure Jira integration");
+    if let Some(jira) = &jira {
+        tokio::spawn(jira.clone().run_poller(
+            pool.clone(),
+            transition_rules.clone(),
+            event_bus.clone(),
+        ));
+    }
+
+    let webhook_addresses = AddressPolicy::from_env();
+    let webhook_worker = DeliveryWorker::from_env(pool.clone(), webhook_addresses.clone())
+        .expect("Failed to configure webhook deliveries");
+    tokio::spawn(webhook_worker.run());
+
+    let jira_webhook_config =
+        JiraWebhook::from_env(pool.clone(), transition_rules.clone(), event_bus.clone())
+            .expect("Failed to configure Jira webhook")
+            .map(web::Data::new);
+
+    let mut schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
         .data(pool.clone())
-        .finish();
+        .data(jwt_keys.clone())
+        .data(transition_rules)
+        .data(ai_provider)
+        .data(ai_rate_limiter)
+        .data(ai_quotas)
+        .data(event_bus)
+        .data(webhook_addresses)
+        .data(Loaders::new(&pool));
+    if let Some(jira) = jira {
+        schema = schema.data(jira);
+    }
+    let schema = schema.finish();
 
     println!("🚀 Server starting on http://127.0.0.1:8080");
     println!("🚀 GraphiQL IDE available at http://127.0.0.1:8080/graphql");
@@ -46,13 +148,32 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(schema.clone()))
+            .app_data(web::Data::new(jwt_keys.clone()))
+            .configure(|cfg| {
+                if let Some(webhook) = &jira_webhook_config {
+                    cfg.app_data(webhook.clone());
+                }
+            })
             .wrap(cors)
             .service(web::resource("/graphql").guard(guard::Post()).to(index)) // Main GraphQL endpoint
+            .service(
+                web::resource("/graphql")
+                    .guard(guard::Get())
+                    .guard(guard::Header("upgrade", "websocket"))
+                    .to(index_ws),
+            )
             .service(
                 web::resource("/graphql")
                     .guard(guard::Get())
                     .to(index_graphiql),
             )
+            .service(
+                web::resource("/webhooks/jira")
+                    .guard(guard::Post())
+                    // Issue payloads carry every field and can be large.
+                    .app_data(web::PayloadConfig::new(2 * 1024 * 1024))
+                    .to(jira_webhook),
+            )
     })
     .bind(("127.0.0.1", 8080))?
     .run()
@@ -0,0 +1,14 @@
+use async_graphql::SimpleObject;
+use uuid::Uuid;
+
+// What AI scoping suggested for a task created from it.
+#[derive(SimpleObject, Clone)]
+pub struct AiTaskScope {
+    #[graphql(skip)]
+    pub task_id: Uuid,
+    // Free text from the model, e.g. "3 days".
+    pub estimate: Option<String>,
+    pub suggested_role: Option<String>,
+    // The version of the prompt that produced the suggestion.
+    pub prompt_version: Option<i32>,
+}
@@ -0,0 +1,39 @@
+use async_graphql::{Enum, ID, SimpleObject};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum AiUsageGroupBy {
+    User,
+    Feature,
+    Model,
+    Day,
+}
+
+// Totals from the ai_requests ledger for one group.
+#[derive(SimpleObject)]
+pub struct AiUsage {
+    // The user's email, feature name, model name or day (YYYY-MM-DD).
+    pub key: String,
+    pub requests: i64,
+    pub failed_requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub average_latency_ms: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct AiQuota {
+    pub user_id: ID,
+    // None means unlimited.
+    pub monthly_tokens: Option<i64>,
+    pub used_this_month: i64,
+}
+
+// A project's shared quota; AI calls made for the project count against it on top
+// of each caller's own quota.
+#[derive(SimpleObject)]
+pub struct AiProjectQuota {
+    pub project_id: ID,
+    // None means the project has no limit of its own.
+    pub monthly_tokens: Option<i64>,
+    pub used_this_month: i64,
+}
@@ -0,0 +1,18 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+// A change made in Jira that the webhook applied to a linked task, or failed to.
+#[derive(SimpleObject, Clone)]
+pub struct JiraChange {
+    #[graphql(skip)]
+    pub task_id: Uuid,
+    pub issue_key: String,
+    // "status", "title", "assignee" or "link".
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub jira_user: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
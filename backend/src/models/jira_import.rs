@@ -0,0 +1,29 @@
+use super::task::Task;
+use async_graphql::{Enum, SimpleObject};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum JiraImportOutcome {
+    Imported,
+    Skipped,
+    // The task could not be created; nothing was changed for the issue.
+    Failed,
+}
+
+// What happened to one issue of an import.
+#[derive(SimpleObject)]
+pub struct JiraImportIssue {
+    pub issue_key: String,
+    pub outcome: JiraImportOutcome,
+    // The created task, for imported issues.
+    pub task: Option<Task>,
+    // Why the issue was skipped or failed, or what could not be carried over.
+    pub notes: Vec<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct JiraImportResult {
+    pub imported: i32,
+    pub skipped: i32,
+    pub failed: i32,
+    pub issues: Vec<JiraImportIssue>,
+}
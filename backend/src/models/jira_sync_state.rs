@@ -0,0 +1,15 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(SimpleObject, Clone)]
+pub struct JiraSyncState {
+    #[graphql(skip)]
+    pub task_id: Uuid,
+    pub issue_key: String,
+    // The issue's status in Jira when last synced.
+    pub jira_status: Option<String>,
+    pub synced_at: Option<DateTime<Utc>>,
+    // Why the last push or pull failed, cleared by the next successful sync.
+    pub last_error: Option<String>,
+}
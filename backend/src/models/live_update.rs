@@ -0,0 +1,96 @@
+use super::{
+    project::Project, project_role::ProjectRole, raci_assignment::RaciAssignment, task::Task,
+};
+use crate::{auth::require_project_role, db::DbPool};
+use async_graphql::{Context, ID, Object, Result};
+use uuid::Uuid;
+
+// The nested objects check the caller's membership again, as it may have ended
+// since they subscribed.
+
+// Delivered by the `taskUpdated` subscription; `task` is null once the task is deleted.
+pub struct TaskUpdate {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+}
+
+#[Object]
+impl TaskUpdate {
+    async fn task_id(&self) -> ID {
+        ID(self.task_id.to_string())
+    }
+
+    async fn task(&self, ctx: &Context<'_>) -> Result<Option<Task>> {
+        let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.project_id, ProjectRole::Viewer).await?;
+        let task = sqlx::query_as!(
+            Task,
+            r#"
+            SELECT id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+            FROM tasks WHERE id = $1 AND project_id = $2
+            "#,
+            self.task_id,
+            self.project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(task)
+    }
+}
+
+// Delivered by the `raciChanged` subscription with the task's current assignments.
+pub struct RaciUpdate {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+}
+
+#[Object]
+impl RaciUpdate {
+    async fn task_id(&self) -> ID {
+        ID(self.task_id.to_string())
+    }
+
+    async fn assignments(&self, ctx: &Context<'_>) -> Result<Vec<RaciAssignment>> {
+        let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.project_id, ProjectRole::Viewer).await?;
+        let assignments = sqlx::query_as!(
+            RaciAssignment,
+            r#"
+            SELECT user_id, task_id, role AS "role: _"
+            FROM raci_assignments WHERE task_id = $1
+            "#,
+            self.task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(assignments)
+    }
+}
+
+// Delivered by the `projectChanged` subscription; `project` is null once the project is deleted.
+pub struct ProjectUpdate {
+    pub project_id: Uuid,
+}
+
+#[Object]
+impl ProjectUpdate {
+    async fn project_id(&self) -> ID {
+        ID(self.project_id.to_string())
+    }
+
+    async fn project(&self, ctx: &Context<'_>) -> Result<Option<Project>> {
+        let pool = ctx.data::<DbPool>()?;
+        let project = sqlx::query_as!(
+            Project,
+            "SELECT id, name, description, deadline, owner_id, archived_at FROM projects WHERE id = $1",
+            self.project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        // Membership goes with a deleted project, so only an existing one is checked.
+        if project.is_some() {
+            require_project_role(ctx, self.project_id, ProjectRole::Viewer).await?;
+        }
+        Ok(project)
+    }
+}
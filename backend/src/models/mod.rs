@@ -1,9 +1,28 @@
+pub mod ai_task_scope;
+pub mod ai_usage;
 pub mod growth_template;
+pub mod jira_change;
+pub mod jira_import;
+pub mod jira_sync_state;
+pub mod live_update;
+pub mod notification;
+pub mod notification_preferences;
 pub mod project;
+pub mod project_member;
+pub mod project_role;
+pub mod project_summary;
+pub mod prompt_template;
 pub mod raci_assignment;
 pub mod raci_role;
+pub mod raci_suggestion;
 pub mod task;
 pub mod task_status;
+pub mod task_status_change;
 pub mod user;
+pub mod user_role;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
+pub mod workflow_instance;
 pub mod workflow_step;
 pub mod workflow_template;
+pub mod workflow_template_draft;
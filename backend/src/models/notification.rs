@@ -0,0 +1,148 @@
+use super::{
+    project_role::ProjectRole, raci_role::RaciRole, task::Task, task_status::TaskStatus, user::User,
+};
+use crate::{
+    auth::require_task_role,
+    graphql::{
+        loaders::Loaders,
+        pagination::{SortKey, SortKind},
+    },
+};
+use async_graphql::{Context, Enum, ID, Object, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq)]
+#[sqlx(type_name = "notification_kind", rename_all = "PascalCase")]
+#[graphql(rename_items = "PascalCase")]
+pub enum NotificationKind {
+    // The user was made Responsible or Accountable for a task.
+    Assigned,
+    // A task the user is Informed about changed status.
+    StatusChanged,
+}
+
+#[derive(FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub task_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub role: Option<RaciRole>,
+    pub from_status: Option<TaskStatus>,
+    pub to_status: Option<TaskStatus>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// The inbox lists the newest notifications first.
+pub const NOTIFICATION_SORT_KEY: SortKey = SortKey {
+    name: "created_at",
+    expr: "n.created_at",
+    column: "n.created_at",
+    kind: SortKind::Timestamp,
+};
+
+#[Object]
+impl Notification {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn kind(&self) -> NotificationKind {
+        self.kind
+    }
+    // Kept even when the task is no longer visible to the user.
+    async fn task_id(&self) -> ID {
+        ID(self.task_id.to_string())
+    }
+    // Only readable while the user can still see the task's project.
+    async fn task(&self, ctx: &Context<'_>) -> Result<Task> {
+        require_task_role(ctx, self.task_id, ProjectRole::Viewer).await?;
+        let task = ctx.data::<Loaders>()?.tasks.load_one(self.task_id).await?;
+        task.ok_or_else(|| async_graphql::Error::new("Task not found"))
+    }
+    async fn actor(&self, ctx: &Context<'_>) -> Result<Option<User>> {
+        let Some(actor_id) = self.actor_id else {
+            return Ok(None);
+        };
+        let user = ctx.data::<Loaders>()?.users.load_one(actor_id).await?;
+        Ok(user)
+    }
+    // Set on assignments.
+    async fn role(&self) -> Option<RaciRole> {
+        self.role
+    }
+    // Set on status changes.
+    async fn from_status(&self) -> Option<TaskStatus> {
+        self.from_status
+    }
+    async fn to_status(&self) -> Option<TaskStatus> {
+        self.to_status
+    }
+    async fn read(&self) -> bool {
+        self.read_at.is_some()
+    }
+    async fn read_at(&self) -> Option<DateTime<Utc>> {
+        self.read_at
+    }
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+// Tells a user they were given a role on a task. Only Responsible and Accountable
+// are announced, and nobody is notified of their own doing.
+pub async fn notify_assignment(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    user_id: Uuid,
+    role: RaciRole,
+    actor_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    if !matches!(role, RaciRole::Responsible | RaciRole::Accountable) {
+        return Ok(());
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO notifications (user_id, kind, task_id, actor_id, role)
+        SELECT $1::uuid, 'Assigned', $2, $3::uuid, $4
+        WHERE $1::uuid IS DISTINCT FROM $3::uuid
+            AND COALESCE((SELECT assignments FROM notification_preferences WHERE user_id = $1), TRUE)
+        "#,
+        user_id,
+        task_id,
+        actor_id,
+        role as _
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// Tells everyone Informed about a task that its status changed.
+pub async fn notify_status_change(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    from: TaskStatus,
+    to: TaskStatus,
+    actor_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO notifications (user_id, kind, task_id, actor_id, from_status, to_status)
+        SELECT r.user_id, 'StatusChanged', r.task_id, $2::uuid, $3, $4
+        FROM raci_assignments r
+        LEFT JOIN notification_preferences p ON p.user_id = r.user_id
+        WHERE r.task_id = $1 AND r.role = 'Informed' AND r.user_id IS DISTINCT FROM $2::uuid
+            AND COALESCE(p.status_changes, TRUE)
+        "#,
+        task_id,
+        actor_id,
+        from as _,
+        to as _
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
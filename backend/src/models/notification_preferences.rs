@@ -0,0 +1,26 @@
+use async_graphql::{InputObject, SimpleObject};
+
+// Which notifications a user receives. Everything is on until turned off.
+#[derive(SimpleObject)]
+pub struct NotificationPreferences {
+    // Being made Responsible or Accountable for a task.
+    pub assignments: bool,
+    // Status changes of tasks the user is Informed about.
+    pub status_changes: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            assignments: true,
+            status_changes: true,
+        }
+    }
+}
+
+// Omitted fields are left unchanged.
+#[derive(InputObject)]
+pub struct NotificationPreferencesInput {
+    pub assignments: Option<bool>,
+    pub status_changes: Option<bool>,
+}
@@ -1,10 +1,38 @@
-use super::task::Task;
-use crate::db::DbPool;
-use async_graphql::{Context, ID, Object};
+use super::{
+    ai_usage::AiProjectQuota,
+    project_member::ProjectMember,
+    project_role::ProjectRole,
+    project_summary::ProjectSummary,
+    task::{Task, TaskFilter, TaskOrder},
+    task_status::TaskStatus,
+    workflow_instance::WorkflowInstance,
+};
+use crate::{
+    ai::{
+        ledger::{begin_ai_call, project_monthly_usage},
+        project_summarizer::{
+            ProjectSnapshot, StatusChangeSnapshot, TaskSnapshot, summarize_project,
+        },
+        prompts::SUMMARIZE_PROJECT,
+    },
+    auth::require_project_role,
+    db::DbPool,
+    graphql::{
+        loaders::{Loaders, TaskPageArgs},
+        pagination::{Cursor, SortDirection, SortKey, SortKind, escape_like},
+    },
+};
+use async_graphql::{
+    Context, Enum, ErrorExtensions, ID, InputObject, Object, connection::Connection,
+};
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+// How many of the latest status changes the summary is given.
+const SUMMARY_RECENT_CHANGES: i64 = 20;
+
 #[derive(FromRow)]
 pub struct Project {
     pub id: Uuid,
@@ -12,6 +40,7 @@ pub struct Project {
     pub description: Option<String>,
     pub deadline: Option<DateTime<Utc>>,
     pub owner_id: Uuid,
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 #[Object]
@@ -31,19 +60,274 @@ impl Project {
     async fn owner_id(&self) -> ID {
         ID(self.owner_id.to_string())
     }
+    async fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+    async fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
     // Resolver Field
-    async fn tasks(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Task>> {
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: TaskFilter,
+        #[graphql(default)] order_by: TaskOrder,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<Cursor, Task>> {
+        require_project_role(ctx, self.id, ProjectRole::Viewer).await?;
+        let args = TaskPageArgs {
+            filter,
+            order_by,
+            first,
+            after,
+        };
+        let page = args.page()?;
+        let rows = ctx
+            .data::<Loaders>()?
+            .project_tasks
+            .load_one((self.id, args))
+            .await?
+            .unwrap_or_default();
+        page.connection(&rows)
+    }
+
+    async fn members(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<ProjectMember>> {
+        let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.id, ProjectRole::Viewer).await?;
+        let members = sqlx::query_as!(
+            ProjectMember,
+            r#"
+            SELECT project_id, user_id, role AS "role: _"
+            FROM project_members WHERE project_id = $1
+            "#,
+            self.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(members)
+    }
+
+    async fn workflow_instances(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<WorkflowInstance>> {
         let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.id, ProjectRole::Viewer).await?;
+        let instances = sqlx::query_as!(
+            WorkflowInstance,
+            "SELECT id, project_id, template_id, template_version, applied_by_id, created_at, updated_at
+            FROM workflow_instances WHERE project_id = $1 ORDER BY created_at ASC",
+            self.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(instances)
+    }
+
+    // The project's AI token quota and what its AI calls have used this month.
+    async fn ai_quota(&self, ctx: &Context<'_>) -> async_graphql::Result<AiProjectQuota> {
+        let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.id, ProjectRole::Viewer).await?;
+        let (monthly_tokens, used_this_month) = project_monthly_usage(pool, self.id).await?;
+        Ok(AiProjectQuota {
+            project_id: ID(self.id.to_string()),
+            monthly_tokens,
+            used_this_month,
+        })
+    }
+
+    // Written by the AI provider on first request and cached until the
+    // project's tasks or RACI assignments, or the summary prompt, change.
+    async fn ai_summary(&self, ctx: &Context<'_>) -> async_graphql::Result<ProjectSummary> {
+        let pool = ctx.data::<DbPool>()?;
+        require_project_role(ctx, self.id, ProjectRole::Viewer).await?;
+
+        let fingerprint = sqlx::query_scalar!(
+            r#"
+            SELECT md5(
+                COALESCE((SELECT string_agg(t::text, ',' ORDER BY t.id) FROM tasks t WHERE t.project_id = $1), '')
+                || COALESCE((
+                    SELECT string_agg(r::text, ',' ORDER BY r.task_id, r.user_id)
+                    FROM raci_assignments r JOIN tasks t ON t.id = r.task_id
+                    WHERE t.project_id = $1
+                ), '')
+            ) AS "fingerprint!"
+            "#,
+            self.id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        // Only a summary written with the prompt version in use counts.
+        let cached = sqlx::query_as!(
+            ProjectSummary,
+            r#"
+            SELECT s.summary, s.generated_at, p.version AS "prompt_version?"
+            FROM project_summaries s JOIN prompt_templates p ON p.id = s.prompt_template_id
+            WHERE s.project_id = $1 AND s.tasks_fingerprint = $2
+                AND p.version = (SELECT MAX(version) FROM prompt_templates WHERE name = p.name)
+            "#,
+            self.id,
+            fingerprint
+        )
+        .fetch_optional(pool)
+        .await?;
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+
         let tasks = sqlx::query_as!(
-            Task,
+            TaskSnapshot,
             r#"
-            SELECT id, title, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
-            FROM tasks WHERE project_id = $1
+            SELECT
+                t.title,
+                t.status AS "status: _",
+                t.deadline,
+                COALESCE((SELECT array_agg(r.user_id ORDER BY r.user_id) FROM raci_assignments r
+                    WHERE r.task_id = t.id AND r.role = 'Responsible'), '{}') AS "responsible!",
+                COALESCE((SELECT array_agg(r.user_id ORDER BY r.user_id) FROM raci_assignments r
+                    WHERE r.task_id = t.id AND r.role = 'Accountable'), '{}') AS "accountable!",
+                CASE WHEN t.status = 'Blocked' THEN (
+                    SELECT h.comment FROM task_status_history h
+                    WHERE h.task_id = t.id AND h.to_status = 'Blocked'
+                    ORDER BY h.changed_at DESC LIMIT 1
+                ) END AS blocked_reason
+            FROM tasks t WHERE t.project_id = $1
+            ORDER BY t.created_at ASC
             "#,
             self.id
         )
         .fetch_all(pool)
         .await?;
-        Ok(tasks)
+
+        let recent_changes = sqlx::query_as!(
+            StatusChangeSnapshot,
+            r#"
+            SELECT t.title AS task_title, h.from_status AS "from_status: _", h.to_status AS "to_status: _", h.comment, h.changed_at
+            FROM task_status_history h JOIN tasks t ON t.id = h.task_id
+            WHERE t.project_id = $1
+            ORDER BY h.changed_at DESC LIMIT $2
+            "#,
+            self.id,
+            SUMMARY_RECENT_CHANGES
+        )
+        .fetch_all(pool)
+        .await?;
+
+        // The provider only sees user ids; they are swapped for emails in its answer.
+        let user_ids: Vec<Uuid> = tasks
+            .iter()
+            .flat_map(|task| task.responsible.iter().chain(&task.accountable))
+            .copied()
+            .collect();
+        let people: HashMap<Uuid, String> =
+            sqlx::query!("SELECT id, email FROM users WHERE id = ANY($1)", &user_ids)
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user.email))
+                .collect();
+
+        let mut status_counts: HashMap<TaskStatus, usize> = HashMap::new();
+        for task in &tasks {
+            *status_counts.entry(task.status).or_insert(0) += 1;
+        }
+
+        let snapshot = ProjectSnapshot {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            deadline: self.deadline,
+            status_counts,
+            tasks,
+            recent_changes,
+        };
+
+        let ai = begin_ai_call(ctx, SUMMARIZE_PROJECT, Some(self.id)).await?;
+        let template = ai.template();
+        let summary = summarize_project(&ai, template, &snapshot, &people)
+            .await
+            .map_err(|e| e.extend())?;
+
+        let summary = sqlx::query_as!(
+            ProjectSummary,
+            r#"INSERT INTO project_summaries (project_id, summary, tasks_fingerprint, prompt_template_id) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (project_id) DO UPDATE
+            SET summary = EXCLUDED.summary, tasks_fingerprint = EXCLUDED.tasks_fingerprint,
+                prompt_template_id = EXCLUDED.prompt_template_id, generated_at = NOW()
+            RETURNING summary, generated_at, $5::INT AS "prompt_version""#,
+            self.id,
+            summary,
+            fingerprint,
+            template.id,
+            template.version
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(summary)
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct ProjectFilter {
+    // Case-insensitive substring of the project name.
+    pub name: Option<String>,
+    // Lists archived projects instead of active ones.
+    #[graphql(default)]
+    pub archived: bool,
+}
+
+impl ProjectFilter {
+    pub fn push_conditions(self, query: &mut QueryBuilder<'_, Postgres>) {
+        if self.archived {
+            query.push(" AND p.archived_at IS NOT NULL");
+        } else {
+            query.push(" AND p.archived_at IS NULL");
+        }
+        if let Some(name) = self.name {
+            query.push(" AND p.name ILIKE ");
+            query.push_bind(format!("%{}%", escape_like(&name)));
+        }
     }
 }
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ProjectOrderField {
+    #[default]
+    Name,
+    CreatedAt,
+    Deadline,
+}
+
+impl ProjectOrderField {
+    pub fn sort_key(self) -> SortKey {
+        match self {
+            Self::Name => SortKey {
+                name: "name",
+                expr: "p.name",
+                column: "p.name",
+                kind: SortKind::Text,
+            },
+            Self::CreatedAt => SortKey {
+                name: "created_at",
+                expr: "p.created_at",
+                column: "p.created_at",
+                kind: SortKind::Timestamp,
+            },
+            Self::Deadline => SortKey {
+                name: "deadline",
+                expr: "COALESCE(p.deadline, 'infinity'::timestamptz)",
+                column: "p.deadline",
+                kind: SortKind::Timestamp,
+            },
+        }
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct ProjectOrder {
+    #[graphql(default)]
+    pub field: ProjectOrderField,
+    #[graphql(default)]
+    pub direction: SortDirection,
+}
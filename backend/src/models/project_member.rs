@@ -0,0 +1,52 @@
+use super::{project_role::ProjectRole, user::User};
+use crate::graphql::loaders::Loaders;
+use async_graphql::{Context, ID, Object, Result};
+use sqlx::{Executor, FromRow, Postgres};
+use uuid::Uuid;
+
+#[derive(FromRow)]
+pub struct ProjectMember {
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub role: ProjectRole,
+}
+
+#[Object]
+impl ProjectMember {
+    async fn project_id(&self) -> ID {
+        ID(self.project_id.to_string())
+    }
+
+    async fn user(&self, ctx: &Context<'_>) -> Result<User> {
+        let user = ctx.data::<Loaders>()?.users.load_one(self.user_id).await?;
+        user.ok_or_else(|| async_graphql::Error::new("User not found"))
+    }
+
+    async fn role(&self) -> ProjectRole {
+        self.role
+    }
+}
+
+// Fails unless every user is a member of the project. Work in a project is only
+// given to its members.
+pub async fn require_members<'c, E>(executor: E, project_id: Uuid, user_ids: &[Uuid]) -> Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let outsiders = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM UNNEST($2::uuid[]) AS u(id)
+        WHERE NOT EXISTS (SELECT 1 FROM project_members m WHERE m.project_id = $1 AND m.user_id = u.id)
+        "#,
+        project_id,
+        user_ids
+    )
+    .fetch_one(executor)
+    .await?;
+    if outsiders > 0 {
+        return Err(async_graphql::Error::new(
+            "Only members of the project can be assigned to its tasks",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use async_graphql::Enum;
+
+// Variants are declared from least to most access, so `Ord` ranks them.
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[sqlx(type_name = "project_role", rename_all = "PascalCase")]
+#[graphql(rename_items = "PascalCase")]
+pub enum ProjectRole {
+    Viewer,
+    Editor,
+    Owner,
+}
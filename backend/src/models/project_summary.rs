@@ -0,0 +1,11 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+
+// An AI-written status report, reused until the project's tasks change.
+#[derive(SimpleObject)]
+pub struct ProjectSummary {
+    pub summary: String,
+    pub generated_at: DateTime<Utc>,
+    // Missing for summaries written before prompts were versioned.
+    pub prompt_version: Option<i32>,
+}
@@ -0,0 +1,41 @@
+use async_graphql::{ID, Object};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// One version of a named AI prompt.
+#[derive(FromRow)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub version: i32,
+    pub system_prompt: String,
+    pub user_template: String,
+    pub created_by_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[Object]
+impl PromptTemplate {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn name(&self) -> &str {
+        &self.name
+    }
+    async fn version(&self) -> i32 {
+        self.version
+    }
+    async fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+    async fn user_template(&self) -> &str {
+        &self.user_template
+    }
+    async fn created_by_id(&self) -> Option<ID> {
+        self.created_by_id.map(|id| ID(id.to_string()))
+    }
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -1,10 +1,10 @@
 use super::{raci_role::RaciRole, user::User};
-use crate::db::DbPool;
-use async_graphql::{Context, Object, Result};
+use crate::graphql::loaders::Loaders;
+use async_graphql::{Context, ID, Object, Result};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct RaciAssignment {
     pub user_id: Uuid,
     pub task_id: Uuid,
@@ -14,15 +14,12 @@ pub struct RaciAssignment {
 #[Object]
 impl RaciAssignment {
     async fn user(&self, ctx: &Context<'_>) -> Result<User> {
-        let pool = ctx.data::<DbPool>()?;
-        let user = sqlx::query_as!(
-            User,
-            "SELECT id, email FROM users WHERE id = $1",
-            self.user_id
-        )
-        .fetch_one(pool)
-        .await?;
-        Ok(user)
+        let user = ctx.data::<Loaders>()?.users.load_one(self.user_id).await?;
+        user.ok_or_else(|| async_graphql::Error::new("User not found"))
+    }
+
+    async fn task_id(&self) -> ID {
+        ID(self.task_id.to_string())
     }
 
     async fn role(&self) -> RaciRole {
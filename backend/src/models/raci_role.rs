@@ -1,6 +1,7 @@
 use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
 
-#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq)]
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[sqlx(type_name = "raci_role", rename_all = "PascalCase")]
 #[graphql(rename_items = "PascalCase")]
 pub enum RaciRole {
@@ -0,0 +1,39 @@
+use super::{raci_role::RaciRole, user::User};
+use crate::graphql::loaders::Loaders;
+use async_graphql::{Context, Object, Result};
+use uuid::Uuid;
+
+// An AI-ranked candidate for one RACI role on a task.
+pub struct RaciSuggestion {
+    pub role: RaciRole,
+    pub user_id: Uuid,
+    // 1 is the best match for the role.
+    pub rank: i32,
+    pub rationale: String,
+    pub prompt_version: i32,
+}
+
+#[Object]
+impl RaciSuggestion {
+    async fn role(&self) -> RaciRole {
+        self.role
+    }
+
+    async fn user(&self, ctx: &Context<'_>) -> Result<User> {
+        let user = ctx.data::<Loaders>()?.users.load_one(self.user_id).await?;
+        user.ok_or_else(|| async_graphql::Error::new("User not found"))
+    }
+
+    async fn rank(&self) -> i32 {
+        self.rank
+    }
+
+    async fn rationale(&self) -> &str {
+        &self.rationale
+    }
+
+    // The version of the prompt that produced this suggestion.
+    async fn prompt_version(&self) -> i32 {
+        self.prompt_version
+    }
+}
@@ -1,14 +1,35 @@
-use super::{raci_assignment::RaciAssignment, task_status::TaskStatus};
-use crate::db::DbPool;
-use async_graphql::{Context, ID, Object};
+use super::{
+    ai_task_scope::AiTaskScope,
+    jira_change::JiraChange,
+    jira_sync_state::JiraSyncState,
+    notification::notify_status_change,
+    project_member::require_members,
+    raci_assignment::RaciAssignment,
+    task_status::{TaskStatus, TransitionRules},
+    task_status_change::TaskStatusChange,
+    user::User,
+};
+use crate::{
+    db::DbPool,
+    graphql::{
+        loaders::Loaders,
+        pagination::{SortDirection, SortKey, SortKind},
+    },
+    webhooks::event::{WebhookEvent, enqueue},
+};
+use async_graphql::{Context, Enum, ID, InputObject, MaybeUndefined, Object};
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{FromRow, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-#[derive(FromRow)]
+// The length of the tasks.title column, in characters.
+const MAX_TITLE_LENGTH: usize = 255;
+
+#[derive(FromRow, Clone)]
 pub struct Task {
     pub id: Uuid,
     pub title: String,
+    pub description: Option<String>,
     pub project_id: Uuid,
     pub assigned_to_id: Option<Uuid>,
     pub status: TaskStatus,
@@ -24,15 +45,37 @@ impl Task {
     async fn title(&self) -> &str {
         &self.title
     }
+    async fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
     async fn project_id(&self) -> ID {
         ID(self.project_id.to_string())
     }
     async fn assigned_to_id(&self) -> Option<ID> {
         self.assigned_to_id.map(|id| ID(id.to_string()))
     }
+    async fn assignee(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<User>> {
+        let Some(assigned_to_id) = self.assigned_to_id else {
+            return Ok(None);
+        };
+        let user = ctx
+            .data::<Loaders>()?
+            .users
+            .load_one(assigned_to_id)
+            .await?;
+        Ok(user)
+    }
     async fn status(&self) -> TaskStatus {
         self.status
     }
+    // The statuses this task may move to next.
+    async fn allowed_next_statuses(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<TaskStatus>> {
+        let rules = ctx.data::<TransitionRules>()?;
+        Ok(rules.allowed_from(self.status).to_vec())
+    }
     async fn deadline(&self) -> Option<DateTime<Utc>> {
         self.deadline
     }
@@ -41,21 +84,318 @@ impl Task {
         self.jira_ticket_id.as_deref()
     }
 
+    // Changes made to this task from Jira, newest first.
+    async fn jira_changes(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<JiraChange>> {
+        let changes = ctx
+            .data::<Loaders>()?
+            .jira_changes
+            .load_one(self.id)
+            .await?;
+        Ok(changes.unwrap_or_default())
+    }
+
+    // What the Jira sync last saw of the linked issue.
+    async fn jira_sync(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<JiraSyncState>> {
+        let Some(issue_key) = &self.jira_ticket_id else {
+            return Ok(None);
+        };
+        let state = ctx
+            .data::<Loaders>()?
+            .jira_sync
+            .load_one((self.id, issue_key.clone()))
+            .await?;
+        Ok(state)
+    }
+
+    // The estimate and role suggested for tasks created by AI scoping.
+    async fn ai_scope(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<AiTaskScope>> {
+        let scope = ctx
+            .data::<Loaders>()?
+            .ai_task_scopes
+            .load_one(self.id)
+            .await?;
+        Ok(scope)
+    }
+
     async fn raci_assignments(
         &self,
         ctx: &Context<'_>,
     ) -> async_graphql::Result<Vec<RaciAssignment>> {
-        let pool = ctx.data::<DbPool>()?;
-        let assignments = sqlx::query_as!(
-            RaciAssignment,
+        let assignments = ctx.data::<Loaders>()?.task_raci.load_one(self.id).await?;
+        Ok(assignments.unwrap_or_default())
+    }
+
+    async fn status_history(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<TaskStatusChange>> {
+        let history = ctx
+            .data::<Loaders>()?
+            .task_status_history
+            .load_one(self.id)
+            .await?;
+        Ok(history.unwrap_or_default())
+    }
+
+    // Tasks that must be completed before this one can start.
+    async fn blocked_by(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Task>> {
+        let tasks = ctx
+            .data::<Loaders>()?
+            .task_blocked_by
+            .load_one(self.id)
+            .await?;
+        Ok(tasks.unwrap_or_default())
+    }
+
+    // Tasks waiting on this one.
+    async fn blocks(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Task>> {
+        let tasks = ctx.data::<Loaders>()?.task_blocks.load_one(self.id).await?;
+        Ok(tasks.unwrap_or_default())
+    }
+}
+
+// Trims a title and checks that it fits the column.
+pub fn check_title(title: &str) -> async_graphql::Result<&str> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(async_graphql::Error::new("Task titles cannot be empty"));
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(async_graphql::Error::new(format!(
+            "Task titles can be at most {} characters",
+            MAX_TITLE_LENGTH
+        )));
+    }
+    Ok(title)
+}
+
+// Moves a task to `status` after checking the transition rules and its
+// dependencies, and records the change in the status history. `changed_by` is
+// None for changes that come from outside, such as Jira.
+pub async fn change_task_status(
+    pool: &DbPool,
+    rules: &TransitionRules,
+    task_id: Uuid,
+    status: TaskStatus,
+    comment: Option<String>,
+    changed_by: Option<Uuid>,
+) -> async_graphql::Result<Task> {
+    let mut tx = pool.begin().await?;
+
+    // Lock the row so concurrent changes are validated against the latest status.
+    let current_status = sqlx::query_scalar!(
+        r#"SELECT status AS "status: TaskStatus" FROM tasks WHERE id = $1 FOR UPDATE"#,
+        task_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    rules.check(current_status, status, comment.as_deref())?;
+
+    // Work can only start once every predecessor is done.
+    if status == TaskStatus::InProgress {
+        let unfinished = sqlx::query_scalar!(
             r#"
-            SELECT user_id, task_id, role AS "role: _"
-            FROM raci_assignments WHERE task_id = $1
+            SELECT COUNT(*) AS "count!"
+            FROM task_dependencies d JOIN tasks t ON t.id = d.depends_on_task_id
+            WHERE d.task_id = $1 AND t.status <> 'Completed'
             "#,
-            self.id
+            task_id
         )
-        .fetch_all(pool)
+        .fetch_one(&mut *tx)
         .await?;
-        Ok(assignments)
+        if unfinished > 0 {
+            return Err(async_graphql::Error::new(format!(
+                "Task is blocked by {} unfinished task(s)",
+                unfinished
+            )));
+        }
+    }
+
+    let updated_task = sqlx::query_as!(
+        Task,
+        r#"
+        UPDATE tasks SET status = $1, updated_at = NOW() WHERE id = $2
+        RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+        "#,
+        status as _, // Cast the Rust enum to the DB enum type
+        task_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO task_status_history (task_id, from_status, to_status, changed_by_id, comment)
+        VALUES ($1, $2, $3, $4, $5)",
+        task_id,
+        current_status as _,
+        status as _,
+        changed_by,
+        comment
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    notify_status_change(&mut tx, task_id, current_status, status, changed_by).await?;
+    enqueue(
+        &mut *tx,
+        WebhookEvent::TaskStatusChanged {
+            task: &updated_task,
+            from: current_status,
+            changed_by,
+            comment: comment.as_deref(),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(updated_task)
+}
+
+// Applies `input` to a task, leaving omitted fields untouched.
+pub async fn update_task_fields(
+    pool: &DbPool,
+    task_id: Uuid,
+    input: UpdateTaskInput,
+) -> async_graphql::Result<Task> {
+    let assigned_to_id = input
+        .assigned_to_id
+        .map_value(|id| Uuid::parse_str(&id))
+        .transpose()?;
+    if let MaybeUndefined::Value(user_id) = assigned_to_id {
+        let project_id = sqlx::query_scalar!("SELECT project_id FROM tasks WHERE id = $1", task_id)
+            .fetch_one(pool)
+            .await?;
+        require_members(pool, project_id, &[user_id]).await?;
+    }
+
+    // Only the given fields are written, in one statement, so concurrent updates of
+    // different fields do not undo each other.
+    let set_description = !input.description.is_undefined();
+    let set_deadline = !input.deadline.is_undefined();
+    let set_assigned_to_id = !assigned_to_id.is_undefined();
+    let updated_task = sqlx::query_as!(
+        Task,
+        r#"
+        UPDATE tasks SET
+            title = COALESCE($1, title),
+            description = CASE WHEN $2 THEN $3 ELSE description END,
+            deadline = CASE WHEN $4 THEN $5 ELSE deadline END,
+            assigned_to_id = CASE WHEN $6 THEN $7 ELSE assigned_to_id END,
+            updated_at = NOW()
+        WHERE id = $8
+        RETURNING id, title, description, project_id, assigned_to_id, status AS "status: _", deadline, jira_ticket_id
+        "#,
+        input.title,
+        set_description,
+        input.description.take(),
+        set_deadline,
+        input.deadline.take(),
+        set_assigned_to_id,
+        assigned_to_id.take(),
+        task_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(updated_task)
+}
+
+// Fields for `update_task`. Omitted fields are left untouched, explicit nulls clear them.
+#[derive(InputObject, Default)]
+pub struct UpdateTaskInput {
+    pub title: Option<String>,
+    pub description: MaybeUndefined<String>,
+    pub deadline: MaybeUndefined<DateTime<Utc>>,
+    pub assigned_to_id: MaybeUndefined<ID>,
+}
+
+#[derive(InputObject, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TaskFilter {
+    // Matches any of the given statuses.
+    pub status: Option<Vec<TaskStatus>>,
+    // An explicit null matches unassigned tasks.
+    pub assignee_id: MaybeUndefined<ID>,
+    pub deadline_from: Option<DateTime<Utc>>,
+    pub deadline_to: Option<DateTime<Utc>>,
+    pub has_jira_link: Option<bool>,
+}
+
+impl TaskFilter {
+    pub fn push_conditions(
+        self,
+        query: &mut QueryBuilder<'_, Postgres>,
+    ) -> async_graphql::Result<()> {
+        if let Some(statuses) = self.status {
+            query.push(" AND t.status = ANY(");
+            query.push_bind(statuses);
+            query.push(")");
+        }
+        match self.assignee_id {
+            MaybeUndefined::Value(assignee_id) => {
+                query.push(" AND t.assigned_to_id = ");
+                query.push_bind(Uuid::parse_str(&assignee_id)?);
+            }
+            MaybeUndefined::Null => {
+                query.push(" AND t.assigned_to_id IS NULL");
+            }
+            MaybeUndefined::Undefined => {}
+        }
+        if let Some(from) = self.deadline_from {
+            query.push(" AND t.deadline >= ");
+            query.push_bind(from);
+        }
+        if let Some(to) = self.deadline_to {
+            query.push(" AND t.deadline <= ");
+            query.push_bind(to);
+        }
+        if let Some(has_jira_link) = self.has_jira_link {
+            query.push(if has_jira_link {
+                " AND t.jira_ticket_id IS NOT NULL"
+            } else {
+                " AND t.jira_ticket_id IS NULL"
+            });
+        }
+        Ok(())
     }
 }
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum TaskOrderField {
+    #[default]
+    CreatedAt,
+    Deadline,
+    Title,
+}
+
+impl TaskOrderField {
+    pub fn sort_key(self) -> SortKey {
+        match self {
+            Self::CreatedAt => SortKey {
+                name: "created_at",
+                expr: "t.created_at",
+                column: "t.created_at",
+                kind: SortKind::Timestamp,
+            },
+            Self::Deadline => SortKey {
+                name: "deadline",
+                expr: "COALESCE(t.deadline, 'infinity'::timestamptz)",
+                column: "t.deadline",
+                kind: SortKind::Timestamp,
+            },
+            Self::Title => SortKey {
+                name: "title",
+                expr: "t.title",
+                column: "t.title",
+                kind: SortKind::Text,
+            },
+        }
+    }
+}
+
+#[derive(InputObject, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TaskOrder {
+    #[graphql(default)]
+    pub field: TaskOrderField,
+    #[graphql(default)]
+    pub direction: SortDirection,
+}
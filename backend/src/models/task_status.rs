@@ -1,9 +1,10 @@
 use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs};
 
-#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq)]
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[sqlx(type_name = "task_status")]
 #[graphql(rename_items = "PascalCase")]
-
 pub enum TaskStatus {
     #[sqlx(rename = "Not Started")] // Map each variant to its exact DB string
     NotStarted,
@@ -18,3 +19,178 @@ pub enum TaskStatus {
     #[sqlx(rename = "Completed")]
     Completed,
 }
+
+// The graph of allowed status changes, shared with resolvers through the schema data.
+#[derive(Clone, Debug)]
+pub struct TransitionRules {
+    allowed: HashMap<TaskStatus, Vec<TaskStatus>>,
+    // Statuses that can only be entered with a comment explaining why.
+    comment_required: Vec<TaskStatus>,
+}
+
+// The JSON form of the graph, e.g.
+// {"allowed": {"NotStarted": ["InProgress"]}, "commentRequired": ["Blocked"]}.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TransitionConfig {
+    // Statuses missing here cannot be left.
+    allowed: HashMap<TaskStatus, Vec<TaskStatus>>,
+    #[serde(default)]
+    comment_required: Vec<TaskStatus>,
+}
+
+impl TransitionRules {
+    // Reads the graph as JSON from TASK_TRANSITIONS, or from the file named by
+    // TASK_TRANSITIONS_FILE. The default graph is used when neither is set.
+    pub fn from_env() -> Result<Self, String> {
+        let (source, json) = match (
+            env::var("TASK_TRANSITIONS"),
+            env::var("TASK_TRANSITIONS_FILE"),
+        ) {
+            (Ok(_), Ok(_)) => {
+                return Err(
+                    "Set only one of TASK_TRANSITIONS and TASK_TRANSITIONS_FILE".to_string()
+                );
+            }
+            (Ok(json), Err(_)) => ("TASK_TRANSITIONS".to_string(), json),
+            (Err(_), Ok(path)) => {
+                let json = fs::read_to_string(&path)
+                    .map_err(|e| format!("Cannot read TASK_TRANSITIONS_FILE {}: {}", path, e))?;
+                (path, json)
+            }
+            (Err(_), Err(_)) => return Ok(Self::default()),
+        };
+        Self::from_json(&json).map_err(|e| format!("{} is invalid: {}", source, e))
+    }
+
+    // Parses the JSON form of the graph described on `TransitionConfig`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: TransitionConfig = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(Self::new(config.allowed, config.comment_required))
+    }
+
+    pub fn new(
+        allowed: HashMap<TaskStatus, Vec<TaskStatus>>,
+        comment_required: Vec<TaskStatus>,
+    ) -> Self {
+        Self {
+            allowed,
+            comment_required,
+        }
+    }
+
+    pub fn allowed_from(&self, from: TaskStatus) -> &[TaskStatus] {
+        self.allowed.get(&from).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn check(
+        &self,
+        from: TaskStatus,
+        to: TaskStatus,
+        comment: Option<&str>,
+    ) -> async_graphql::Result<()> {
+        if from == to {
+            return Err(async_graphql::Error::new(format!(
+                "Task is already {:?}",
+                to
+            )));
+        }
+        if !self.allowed_from(from).contains(&to) {
+            return Err(async_graphql::Error::new(format!(
+                "Cannot move a task from {:?} to {:?}",
+                from, to
+            )));
+        }
+        let has_comment = comment.is_some_and(|c| !c.trim().is_empty());
+        if self.comment_required.contains(&to) && !has_comment {
+            return Err(async_graphql::Error::new(format!(
+                "A comment is required when moving a task to {:?}",
+                to
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TransitionRules {
+    fn default() -> Self {
+        use TaskStatus::*;
+
+        let allowed = HashMap::from([
+            (NotStarted, vec![InProgress, Blocked, Deprecated]),
+            (
+                InProgress,
+                vec![NotStarted, Blocked, UnderReview, Deprecated],
+            ),
+            (Blocked, vec![NotStarted, InProgress, Deprecated]),
+            (UnderReview, vec![InProgress, Blocked, Completed]),
+            (Completed, vec![InProgress]),
+            (Deprecated, vec![NotStarted]),
+        ]);
+
+        Self::new(allowed, vec![Blocked])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TaskStatus::*;
+
+    #[test]
+    fn parses_a_transition_graph() {
+        let rules = TransitionRules::from_json(
+            r#"{"allowed": {"NotStarted": ["InProgress"], "InProgress": ["Blocked", "Completed"]},
+                "commentRequired": ["Blocked"]}"#,
+        )
+        .unwrap();
+        assert_eq!(rules.allowed_from(NotStarted), [InProgress]);
+        assert_eq!(rules.allowed_from(InProgress), [Blocked, Completed]);
+        // Statuses left out of the graph cannot be left.
+        assert!(rules.allowed_from(Completed).is_empty());
+
+        assert!(rules.check(InProgress, Completed, None).is_ok());
+        assert!(rules.check(InProgress, Blocked, None).is_err());
+        assert!(rules.check(InProgress, Blocked, Some("  ")).is_err());
+        assert!(
+            rules
+                .check(InProgress, Blocked, Some("Waiting on legal"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_status_names() {
+        let error =
+            TransitionRules::from_json(r#"{"allowed": {"NotStarted": ["Done"]}}"#).unwrap_err();
+        assert!(error.contains("unknown variant `Done`"), "{}", error);
+
+        let error = TransitionRules::from_json(r#"{"allowed": {"Todo": []}}"#).unwrap_err();
+        assert!(error.contains("unknown variant `Todo`"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(TransitionRules::from_json("").is_err());
+        assert!(
+            TransitionRules::from_json(r#"{"allowed": {"NotStarted": "InProgress"}}"#).is_err()
+        );
+        assert!(TransitionRules::from_json(r#"{"commentRequired": ["Blocked"]}"#).is_err());
+        let error = TransitionRules::from_json(r#"{"allowed": {}, "comments": []}"#).unwrap_err();
+        assert!(error.contains("unknown field `comments`"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_transitions_outside_the_graph() {
+        let rules = TransitionRules::default();
+        let error = rules.check(Completed, Deprecated, None).unwrap_err();
+        assert_eq!(
+            error.message,
+            "Cannot move a task from Completed to Deprecated"
+        );
+        let error = rules
+            .check(Blocked, Blocked, Some("Still blocked"))
+            .unwrap_err();
+        assert_eq!(error.message, "Task is already Blocked");
+    }
+}
@@ -0,0 +1,47 @@
+use super::{task_status::TaskStatus, user::User};
+use crate::graphql::loaders::Loaders;
+use async_graphql::{Context, ID, Object, Result};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// One row of a task's status history.
+#[derive(FromRow, Clone)]
+pub struct TaskStatusChange {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub from_status: TaskStatus,
+    pub to_status: TaskStatus,
+    pub changed_by_id: Option<Uuid>,
+    pub comment: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[Object]
+impl TaskStatusChange {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn task_id(&self) -> ID {
+        ID(self.task_id.to_string())
+    }
+    async fn from_status(&self) -> TaskStatus {
+        self.from_status
+    }
+    async fn to_status(&self) -> TaskStatus {
+        self.to_status
+    }
+    async fn changed_by(&self, ctx: &Context<'_>) -> Result<Option<User>> {
+        let Some(changed_by_id) = self.changed_by_id else {
+            return Ok(None);
+        };
+        let user = ctx.data::<Loaders>()?.users.load_one(changed_by_id).await?;
+        Ok(user)
+    }
+    async fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    async fn changed_at(&self) -> DateTime<Utc> {
+        self.changed_at
+    }
+}
@@ -1,11 +1,14 @@
-use async_graphql::{ID, Object};
-use sqlx::FromRow;
+use super::user_role::UserRole;
+use crate::graphql::pagination::{SortDirection, SortKey, SortKind, escape_like};
+use async_graphql::{Enum, ID, InputObject, Object};
+use sqlx::{FromRow, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
+    pub role: UserRole,
 }
 
 #[Object]
@@ -18,4 +21,62 @@ impl User {
     async fn email(&self) -> &str {
         &self.email
     }
+
+    async fn role(&self) -> UserRole {
+        self.role
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct UserFilter {
+    pub email_prefix: Option<String>,
+    pub role: Option<UserRole>,
+}
+
+impl UserFilter {
+    pub fn push_conditions(self, query: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(prefix) = self.email_prefix {
+            // Emails are stored lowercased.
+            query.push(" AND u.email LIKE ");
+            query.push_bind(format!("{}%", escape_like(&prefix.to_lowercase())));
+        }
+        if let Some(role) = self.role {
+            query.push(" AND u.role = ");
+            query.push_bind(role);
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum UserOrderField {
+    #[default]
+    Email,
+    CreatedAt,
+}
+
+impl UserOrderField {
+    pub fn sort_key(self) -> SortKey {
+        match self {
+            Self::Email => SortKey {
+                name: "email",
+                expr: "u.email",
+                column: "u.email",
+                kind: SortKind::Text,
+            },
+            Self::CreatedAt => SortKey {
+                name: "created_at",
+                expr: "u.created_at",
+                column: "u.created_at",
+                kind: SortKind::Timestamp,
+            },
+        }
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct UserOrder {
+    #[graphql(default)]
+    pub field: UserOrderField,
+    #[graphql(default)]
+    pub direction: SortDirection,
 }
@@ -0,0 +1,11 @@
+use async_graphql::Enum;
+
+// Variants are declared from least to most privileged, so `Ord` ranks them.
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[sqlx(type_name = "user_role", rename_all = "PascalCase")]
+#[graphql(rename_items = "PascalCase")]
+pub enum UserRole {
+    Employee,
+    Manager,
+    Admin,
+}
@@ -0,0 +1,83 @@
+use super::webhook_subscription::WebhookEventType;
+use crate::graphql::pagination::{SortKey, SortKind};
+use async_graphql::{Enum, ID, Object};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(sqlx::Type, Debug, Enum, Copy, Clone, Eq, PartialEq)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "PascalCase")]
+#[graphql(rename_items = "PascalCase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    // Out of attempts; only a redelivery sends it again.
+    Dead,
+}
+
+// One event sent, or to be sent, to one subscription.
+#[derive(FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event: String,
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Dead letters are listed most recently failed first.
+pub const DEAD_LETTER_SORT_KEY: SortKey = SortKey {
+    name: "last_attempt_at",
+    expr: "d.last_attempt_at",
+    column: "d.last_attempt_at",
+    kind: SortKind::Timestamp,
+};
+
+#[Object]
+impl WebhookDelivery {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn subscription_id(&self) -> ID {
+        ID(self.subscription_id.to_string())
+    }
+    async fn event(&self) -> Option<WebhookEventType> {
+        WebhookEventType::from_name(&self.event)
+    }
+    // The JSON body exactly as it is sent.
+    async fn payload(&self) -> &str {
+        &self.payload
+    }
+    async fn status(&self) -> WebhookDeliveryStatus {
+        self.status
+    }
+    async fn attempts(&self) -> i32 {
+        self.attempts
+    }
+    async fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        (self.status == WebhookDeliveryStatus::Pending).then_some(self.next_attempt_at)
+    }
+    async fn last_attempt_at(&self) -> Option<DateTime<Utc>> {
+        self.last_attempt_at
+    }
+    // The receiver's HTTP status on the last attempt, if it answered at all.
+    async fn last_status_code(&self) -> Option<i32> {
+        self.last_status_code
+    }
+    async fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+    async fn delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivered_at
+    }
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
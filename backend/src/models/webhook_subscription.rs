@@ -0,0 +1,136 @@
+use crate::webhooks::address::AddressPolicy;
+use async_graphql::{Enum, ID, InputObject, Object};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+const MIN_SECRET_LENGTH: usize = 16;
+
+// The events a webhook subscription can receive. They are stored and sent by name.
+#[derive(Debug, Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(rename_items = "PascalCase")]
+pub enum WebhookEventType {
+    TaskCreated,
+    TaskStatusChanged,
+    RaciAssigned,
+    ProjectCreated,
+    WorkflowApplied,
+}
+
+impl WebhookEventType {
+    const ALL: [Self; 5] = [
+        Self::TaskCreated,
+        Self::TaskStatusChanged,
+        Self::RaciAssigned,
+        Self::ProjectCreated,
+        Self::WorkflowApplied,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TaskCreated => "task.created",
+            Self::TaskStatusChanged => "task.status_changed",
+            Self::RaciAssigned => "raci.assigned",
+            Self::ProjectCreated => "project.created",
+            Self::WorkflowApplied => "workflow.applied",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.name() == name)
+    }
+}
+
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_by_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[Object]
+impl WebhookSubscription {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn url(&self) -> &str {
+        &self.url
+    }
+    // Deliveries carry an HMAC-SHA256 of "<timestamp>.<body>" keyed with this secret.
+    async fn secret(&self) -> &str {
+        &self.secret
+    }
+    async fn events(&self) -> Vec<WebhookEventType> {
+        self.events
+            .iter()
+            .filter_map(|name| WebhookEventType::from_name(name))
+            .collect()
+    }
+    // Inactive subscriptions get no new deliveries, and pending ones wait.
+    async fn active(&self) -> bool {
+        self.active
+    }
+    async fn created_by_id(&self) -> Option<ID> {
+        self.created_by_id.map(|id| ID(id.to_string()))
+    }
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+// Omitted fields are left unchanged.
+#[derive(InputObject)]
+pub struct UpdateWebhookSubscriptionInput {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEventType>>,
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+pub async fn check_url(addresses: &AddressPolicy, url: &str) -> async_graphql::Result<()> {
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => parsed,
+        _ => {
+            return Err(async_graphql::Error::new(
+                "Webhook URL must be an absolute http(s) URL",
+            ));
+        }
+    };
+    addresses
+        .check_url(&parsed)
+        .await
+        .map_err(|reason| async_graphql::Error::new(format!("Webhook URL rejected: {}", reason)))
+}
+
+pub fn event_names(events: &[WebhookEventType]) -> async_graphql::Result<Vec<String>> {
+    if events.is_empty() {
+        return Err(async_graphql::Error::new(
+            "A webhook subscription needs at least one event",
+        ));
+    }
+    let mut names: Vec<String> = events
+        .iter()
+        .map(|event| event.name().to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+// Short secrets would make the signatures easy to forge.
+pub fn check_secret(secret: &str) -> async_graphql::Result<()> {
+    if secret.len() < MIN_SECRET_LENGTH {
+        return Err(async_graphql::Error::new(format!(
+            "Webhook secrets must be at least {} characters",
+            MIN_SECRET_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+// 64 hex characters from two random UUIDs.
+pub fn generate_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
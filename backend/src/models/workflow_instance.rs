@@ -0,0 +1,70 @@
+use super::{task::Task, workflow_template::WorkflowTemplate};
+use crate::db::DbPool;
+use async_graphql::{Context, ID, Object, Result};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// A workflow template applied to a project, and the tasks it produced.
+#[derive(FromRow)]
+pub struct WorkflowInstance {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub template_id: Uuid,
+    pub template_version: i32,
+    pub applied_by_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[Object]
+impl WorkflowInstance {
+    async fn id(&self) -> ID {
+        ID(self.id.to_string())
+    }
+    async fn project_id(&self) -> ID {
+        ID(self.project_id.to_string())
+    }
+    async fn template(&self, ctx: &Context<'_>) -> Result<WorkflowTemplate> {
+        let pool = ctx.data::<DbPool>()?;
+        let template = sqlx::query_as!(
+            WorkflowTemplate,
+            "SELECT id, name, description, created_by_id, version FROM workflow_templates WHERE id = $1",
+            self.template_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(template)
+    }
+    // The template version the project's tasks were last synced with.
+    async fn template_version(&self) -> i32 {
+        self.template_version
+    }
+    async fn applied_by_id(&self) -> Option<ID> {
+        self.applied_by_id.map(|id| ID(id.to_string()))
+    }
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+    async fn tasks(&self, ctx: &Context<'_>) -> Result<Vec<Task>> {
+        let pool = ctx.data::<DbPool>()?;
+        let tasks = sqlx::query_as!(
+            Task,
+            r#"
+            SELECT t.id, t.title, t.description, t.project_id, t.assigned_to_id, t.status AS "status: _", t.deadline, t.jira_ticket_id
+            FROM workflow_instance_tasks wt
+            JOIN tasks t ON t.id = wt.task_id
+            JOIN workflow_steps s ON s.id = wt.step_id
+            WHERE wt.instance_id = $1
+            ORDER BY s.step_order ASC
+            "#,
+            self.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(tasks)
+    }
+}
@@ -1,4 +1,4 @@
-use async_graphql::{ID, Object};
+use async_graphql::{ID, InputObject, Object};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -17,6 +17,9 @@ impl WorkflowStep {
     async fn id(&self) -> ID {
         ID(self.id.to_string())
     }
+    async fn template_id(&self) -> ID {
+        ID(self.template_id.to_string())
+    }
     async fn step_name(&self) -> &str {
         &self.step_name
     }
@@ -30,3 +33,10 @@ impl WorkflowStep {
         self.depends_on_step_id.map(|id| ID(id.to_string()))
     }
 }
+
+// Staffs every step with the given `role` when a template is applied.
+#[derive(InputObject)]
+pub struct StepRoleAssignment {
+    pub role: String,
+    pub user_id: ID,
+}
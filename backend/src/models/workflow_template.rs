@@ -1,6 +1,9 @@
 use super::workflow_step::WorkflowStep;
-use crate::db::DbPool;
-use async_graphql::{Context, ID, Object, Result};
+use crate::{
+    db::DbPool,
+    graphql::pagination::{SortDirection, SortKey, SortKind},
+};
+use async_graphql::{Context, Enum, ID, InputObject, Object, Result};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -10,6 +13,8 @@ pub struct WorkflowTemplate {
     pub name: String,
     pub description: Option<String>,
     pub created_by_id: Uuid,
+    // Bumped whenever the template's steps change.
+    pub version: i32,
 }
 
 #[Object]
@@ -23,6 +28,12 @@ impl WorkflowTemplate {
     async fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+    async fn created_by_id(&self) -> ID {
+        ID(self.created_by_id.to_string())
+    }
+    async fn version(&self) -> i32 {
+        self.version
+    }
 
     // This nested resolver fetches all steps for this template, ordered correctly.
     async fn steps(&self, ctx: &Context<'_>) -> Result<Vec<WorkflowStep>> {
@@ -37,3 +48,37 @@ impl WorkflowTemplate {
         Ok(steps)
     }
 }
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum WorkflowTemplateOrderField {
+    #[default]
+    Name,
+    CreatedAt,
+}
+
+impl WorkflowTemplateOrderField {
+    pub fn sort_key(self) -> SortKey {
+        match self {
+            Self::Name => SortKey {
+                name: "name",
+                expr: "w.name",
+                column: "w.name",
+                kind: SortKind::Text,
+            },
+            Self::CreatedAt => SortKey {
+                name: "created_at",
+                expr: "w.created_at",
+                column: "w.created_at",
+                kind: SortKind::Timestamp,
+            },
+        }
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct WorkflowTemplateOrder {
+    #[graphql(default)]
+    pub field: WorkflowTemplateOrderField,
+    #[graphql(default)]
+    pub direction: SortDirection,
+}
@@ -0,0 +1,135 @@
+use async_graphql::{ID, InputObject, SimpleObject};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+// An unsaved workflow template, e.g. one generated by AI, that can be reviewed,
+// edited and passed back to `save_workflow_template_draft`.
+#[derive(SimpleObject, InputObject)]
+#[graphql(input_name = "WorkflowTemplateDraftInput")]
+pub struct WorkflowTemplateDraft {
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStepDraft>,
+    // The version of the prompt that generated the draft, if it was generated.
+    pub prompt_version: Option<i32>,
+}
+
+// Step ids are only meaningful within the draft; saving assigns real ones.
+#[derive(SimpleObject, InputObject)]
+#[graphql(input_name = "WorkflowStepDraftInput")]
+pub struct WorkflowStepDraft {
+    pub id: ID,
+    pub step_name: String,
+    pub step_order: i32,
+    pub role: Option<String>,
+    pub depends_on_step_id: Option<ID>,
+}
+
+impl WorkflowTemplateDraft {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("the template needs a name".to_string());
+        }
+        if self
+            .steps
+            .iter()
+            .any(|step| step.step_name.trim().is_empty())
+        {
+            return Err("every step needs a name".to_string());
+        }
+        let edges: Vec<(&str, Option<&str>)> = self
+            .steps
+            .iter()
+            .map(|step| {
+                (
+                    step.id.as_str(),
+                    step.depends_on_step_id.as_ref().map(|id| id.as_str()),
+                )
+            })
+            .collect();
+        check_step_graph(&edges)
+    }
+}
+
+// Checks that (step, depends_on) pairs form a DAG: ids are unique, every
+// dependency names a known step, and following dependencies never loops.
+pub fn check_step_graph<K>(edges: &[(K, Option<K>)]) -> Result<(), String>
+where
+    K: Eq + Hash + Copy + std::fmt::Debug,
+{
+    let mut parent: HashMap<K, Option<K>> = HashMap::new();
+    for &(step, depends_on) in edges {
+        if parent.insert(step, depends_on).is_some() {
+            return Err(format!("step {:?} appears more than once", step));
+        }
+    }
+
+    for &(step, depends_on) in edges {
+        if let Some(depends_on) = depends_on
+            && !parent.contains_key(&depends_on)
+        {
+            return Err(format!(
+                "step {:?} depends on unknown step {:?}",
+                step, depends_on
+            ));
+        }
+
+        // Each step has at most one dependency, so a cycle shows up as a revisit.
+        let mut seen = HashSet::from([step]);
+        let mut current = depends_on;
+        while let Some(next) = current {
+            if !seen.insert(next) {
+                return Err(format!("step {:?} is part of a dependency cycle", step));
+            }
+            current = parent.get(&next).copied().flatten();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_acyclic_graphs() {
+        assert_eq!(check_step_graph::<u32>(&[]), Ok(()));
+        assert_eq!(
+            check_step_graph(&[(1, None), (2, Some(1)), (3, Some(1)), (4, Some(3))]),
+            Ok(())
+        );
+        // Steps may be listed before the step they depend on.
+        assert_eq!(check_step_graph(&[("b", Some("a")), ("a", None)]), Ok(()));
+    }
+
+    #[test]
+    fn rejects_self_loops() {
+        assert_eq!(
+            check_step_graph(&[(1, None), (2, Some(2))]),
+            Err("step 2 is part of a dependency cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_longer_cycles() {
+        let error = check_step_graph(&[(1, Some(3)), (2, Some(1)), (3, Some(2)), (4, None)]);
+        assert_eq!(
+            error,
+            Err("step 1 is part of a dependency cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_duplicate_steps() {
+        assert_eq!(
+            check_step_graph(&[("a", None), ("b", Some("c"))]),
+            Err(r#"step "b" depends on unknown step "c""#.to_string())
+        );
+        assert_eq!(
+            check_step_graph(&[("a", None), ("a", Some("b"))]),
+            Err(r#"step "a" appears more than once"#.to_string())
+        );
+    }
+}
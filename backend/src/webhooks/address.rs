@@ -0,0 +1,225 @@
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+};
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+use tokio::net::lookup_host;
+
+// Webhooks are sent from inside our network, so they may only go to public
+// addresses; otherwise a subscription could probe internal services and read the
+// answers back from the delivery errors. Hosts listed in WEBHOOK_ALLOWED_HOSTS
+// (comma-separated) are exempt, for internal tools that subscribe on purpose.
+#[derive(Clone, Default)]
+pub struct AddressPolicy {
+    allowed_hosts: Arc<Vec<String>>,
+}
+
+impl AddressPolicy {
+    pub fn from_env() -> Self {
+        let allowed_hosts = env::var("WEBHOOK_ALLOWED_HOSTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|host| host.trim().to_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect();
+        Self {
+            allowed_hosts: Arc::new(allowed_hosts),
+        }
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    // Fails for URLs whose host is an internal IP address. The client does not
+    // resolve such hosts, so they are checked here; host names are left to
+    // `check_url` and the resolver.
+    pub fn check_ip_host(&self, url: &Url) -> Result<(), String> {
+        match ip_host(url) {
+            Some(ip) if is_internal(ip) && !self.allows_host(url.host_str().unwrap_or("")) => {
+                Err(format!("{} is an internal address", ip))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Also resolves host names, and fails if any of their addresses is internal.
+    pub async fn check_url(&self, url: &Url) -> Result<(), String> {
+        self.check_ip_host(url)?;
+        let Some(host) = url.host_str() else {
+            return Ok(());
+        };
+        if ip_host(url).is_some() || self.allows_host(host) {
+            return Ok(());
+        }
+        let port = url.port_or_known_default().unwrap_or(443);
+        let addrs = lookup_host((host, port))
+            .await
+            .map_err(|_| format!("{} could not be resolved", host))?;
+        check_addresses(host, addrs).map(|_| ())
+    }
+}
+
+// The host of the URL if it is an IP address; IPv6 hosts come in brackets.
+fn ip_host(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+fn check_addresses(
+    host: &str,
+    addrs: impl Iterator<Item = SocketAddr>,
+) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = addrs.collect();
+    if addrs.iter().any(|addr| is_internal(addr.ip())) {
+        return Err(format!("{} resolves to an internal address", host));
+    }
+    Ok(addrs)
+}
+
+// Used by the delivery client, so a host that was public when the subscription was
+// saved cannot be pointed at an internal address later.
+impl Resolve for AddressPolicy {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs = lookup_host((host, 0)).await?;
+            let addrs = if policy.allows_host(host) {
+                addrs.collect()
+            } else {
+                check_addresses(host, addrs)?
+            };
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// Loopback, private, link-local, shared (CGNAT), multicast and other non-public ranges.
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_internal_v4(ip),
+        IpAddr::V6(ip) => is_internal_v6(ip),
+    }
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8, 100.64.0.0/10, 192.0.0.0/24, 198.18.0.0/15 and 240.0.0.0/4
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // IPv4-mapped and -compatible addresses, and NAT64 (64:ff9b::/96).
+    if let Some(v4) = ip.to_ipv4() {
+        return is_internal_v4(v4);
+    }
+    if ip.segments()[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_internal_v4(Ipv4Addr::new(a, b, c, d));
+    }
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local (fc00::/7), link-local (fe80::/10) and site-local (fec0::/10).
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xffc0) == 0xfec0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed_hosts: &[&str]) -> AddressPolicy {
+        AddressPolicy {
+            allowed_hosts: Arc::new(allowed_hosts.iter().map(|host| host.to_string()).collect()),
+        }
+    }
+
+    fn check(policy: &AddressPolicy, url: &str) -> Result<(), String> {
+        policy.check_ip_host(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn refuses_internal_ipv4_hosts() {
+        let policy = policy(&[]);
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://10.1.2.3/hook",
+            "http://172.16.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/hook",
+            "http://0.0.0.0/hook",
+            // Decimal form of 127.0.0.1, normalized by the URL parser.
+            "http://2130706433/hook",
+        ] {
+            assert!(check(&policy, url).is_err(), "{} was allowed", url);
+        }
+        assert!(check(&policy, "https://93.184.216.34/hook").is_ok());
+    }
+
+    #[test]
+    fn refuses_internal_ipv6_hosts() {
+        let policy = policy(&[]);
+        for url in [
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:10.0.0.1]/hook",
+            "http://[::ffff:169.254.169.254]/hook",
+            "http://[64:ff9b::7f00:1]/hook",
+        ] {
+            assert!(check(&policy, url).is_err(), "{} was allowed", url);
+        }
+        assert!(check(&policy, "http://[2606:4700::1111]/hook").is_ok());
+        assert!(check(&policy, "http://[::ffff:93.184.216.34]/hook").is_ok());
+    }
+
+    #[test]
+    fn allow_listed_hosts_may_be_internal() {
+        let policy = policy(&["10.0.0.5", "::1", "hooks.internal"]);
+        assert!(check(&policy, "http://10.0.0.5/hook").is_ok());
+        assert!(check(&policy, "http://[::1]/hook").is_ok());
+        assert!(check(&policy, "http://10.0.0.6/hook").is_err());
+        assert!(policy.allows_host("HOOKS.internal"));
+    }
+
+    #[test]
+    fn refuses_names_resolving_to_internal_addresses() {
+        let public: SocketAddr = "93.184.216.34:443".parse().unwrap();
+        let private: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        assert!(check_addresses("example.com", [public].into_iter()).is_ok());
+        assert!(check_addresses("example.com", [public, private].into_iter()).is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_host_names_before_allowing_them() {
+        let url = Url::parse("http://localhost:8080/hook").unwrap();
+        assert!(policy(&[]).check_url(&url).await.is_err());
+        assert!(policy(&["localhost"]).check_url(&url).await.is_ok());
+    }
+}
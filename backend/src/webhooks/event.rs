@@ -0,0 +1,133 @@
+use crate::models::{
+    project::Project, raci_role::RaciRole, task::Task, task_status::TaskStatus,
+    webhook_subscription::WebhookEventType,
+};
+use chrono::Utc;
+use serde_json::{Value, json};
+use sqlx::{Executor, Postgres};
+use uuid::Uuid;
+
+// A change that webhook subscribers are told about.
+pub enum WebhookEvent<'a> {
+    TaskCreated(&'a Task),
+    TaskStatusChanged {
+        task: &'a Task,
+        from: TaskStatus,
+        changed_by: Option<Uuid>,
+        comment: Option<&'a str>,
+    },
+    RaciAssigned {
+        task_id: Uuid,
+        user_id: Uuid,
+        role: RaciRole,
+    },
+    ProjectCreated(&'a Project),
+    WorkflowApplied {
+        instance_id: Uuid,
+        project_id: Uuid,
+        template_id: Uuid,
+        template_version: i32,
+        applied_by: Uuid,
+        tasks: &'a [Task],
+    },
+}
+
+impl WebhookEvent<'_> {
+    fn event_type(&self) -> WebhookEventType {
+        match self {
+            Self::TaskCreated(_) => WebhookEventType::TaskCreated,
+            Self::TaskStatusChanged { .. } => WebhookEventType::TaskStatusChanged,
+            Self::RaciAssigned { .. } => WebhookEventType::RaciAssigned,
+            Self::ProjectCreated(_) => WebhookEventType::ProjectCreated,
+            Self::WorkflowApplied { .. } => WebhookEventType::WorkflowApplied,
+        }
+    }
+
+    fn data(&self) -> Value {
+        match self {
+            Self::TaskCreated(task) => json!({ "task": task_json(task) }),
+            Self::TaskStatusChanged {
+                task,
+                from,
+                changed_by,
+                comment,
+            } => json!({
+                "task": task_json(task),
+                "from": from,
+                "to": task.status,
+                "changedById": changed_by,
+                "comment": comment,
+            }),
+            Self::RaciAssigned {
+                task_id,
+                user_id,
+                role,
+            } => json!({ "taskId": task_id, "userId": user_id, "role": role }),
+            Self::ProjectCreated(project) => json!({
+                "project": {
+                    "id": project.id,
+                    "name": project.name,
+                    "description": project.description,
+                    "deadline": project.deadline,
+                    "ownerId": project.owner_id,
+                }
+            }),
+            Self::WorkflowApplied {
+                instance_id,
+                project_id,
+                template_id,
+                template_version,
+                applied_by,
+                tasks,
+            } => json!({
+                "instanceId": instance_id,
+                "projectId": project_id,
+                "templateId": template_id,
+                "templateVersion": template_version,
+                "appliedById": applied_by,
+                "taskIds": tasks.iter().map(|task| task.id).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+// Field names follow the GraphQL schema.
+fn task_json(task: &Task) -> Value {
+    json!({
+        "id": task.id,
+        "projectId": task.project_id,
+        "title": task.title,
+        "description": task.description,
+        "status": task.status,
+        "assignedToId": task.assigned_to_id,
+        "deadline": task.deadline,
+        "jiraTicketId": task.jira_ticket_id,
+    })
+}
+
+// Queues a delivery of the event to every active subscription that wants it. Called
+// with the transaction that makes the change, so an event goes out exactly when
+// the change commits.
+pub async fn enqueue<'c, E>(executor: E, event: WebhookEvent<'_>) -> Result<(), sqlx::Error>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let name = event.event_type().name();
+    let payload = json!({
+        "id": Uuid::new_v4(),
+        "event": name,
+        "createdAt": Utc::now(),
+        "data": event.data(),
+    })
+    .to_string();
+
+    sqlx::query!(
+        "INSERT INTO webhook_deliveries (subscription_id, event, payload)
+        SELECT id, $1::text, $2 FROM webhook_subscriptions WHERE active AND $1::text = ANY(events)",
+        name,
+        payload
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
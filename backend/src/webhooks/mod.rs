@@ -0,0 +1,3 @@
+pub mod address;
+pub mod delivery;
+pub mod event;
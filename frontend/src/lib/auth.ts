@@ -0,0 +1,16 @@
+import { browser } from '$app/environment';
+
+// The JWT returned by the `login` mutation, kept across reloads.
+const TOKEN_KEY = 'clarika_token';
+
+export function getToken(): string | null {
+  return browser ? localStorage.getItem(TOKEN_KEY) : null;
+}
+
+export function setToken(token: string) {
+  localStorage.setItem(TOKEN_KEY, token);
+}
+
+export function clearToken() {
+  localStorage.removeItem(TOKEN_KEY);
+}
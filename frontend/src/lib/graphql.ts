@@ -0,0 +1,37 @@
+import { GraphQLClient } from 'graphql-request';
+import { getToken } from './auth';
+
+export const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
+
+// Shared client; the bearer token is read on every request so logging in or out
+// takes effect immediately.
+export const client = new GraphQLClient(GQL_ENDPOINT, {
+  headers: (): Record<string, string> => {
+    const token = getToken();
+    return token ? { Authorization: `Bearer ${token}` } : {};
+  },
+});
+
+export interface Connection<T> {
+  edges: { node: T }[];
+  pageInfo: { hasNextPage: boolean; endCursor: string | null };
+}
+
+// Lists are paginated, so this follows `pageInfo` until every node is loaded. The
+// query must take an `$after: String` variable, and `select` picks the connection
+// out of each response.
+export async function fetchAllPages<R, T>(
+  query: string,
+  select: (response: R) => Connection<T> | null | undefined,
+  variables: Record<string, unknown> = {},
+): Promise<T[]> {
+  const nodes: T[] = [];
+  let after: string | null = null;
+  do {
+    const connection = select(await client.request<R>(query, { ...variables, after }));
+    if (!connection) break;
+    nodes.push(...connection.edges.map((edge) => edge.node));
+    after = connection.pageInfo.hasNextPage ? connection.pageInfo.endCursor : null;
+  } while (after);
+  return nodes;
+}
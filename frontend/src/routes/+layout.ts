@@ -0,0 +1,15 @@
+import { redirect } from '@sveltejs/kit';
+import { getToken } from '$lib/auth';
+import type { LayoutLoad } from './$types';
+
+// The token lives in localStorage, so pages are rendered in the browser only.
+export const ssr = false;
+
+// Pages that work without logging in.
+const PUBLIC_ROUTES = ['/', '/login', '/register'];
+
+export const load: LayoutLoad = ({ url }) => {
+  if (!getToken() && !PUBLIC_ROUTES.includes(url.pathname)) {
+    redirect(307, `/login?redirectTo=${encodeURIComponent(url.pathname + url.search)}`);
+  }
+};
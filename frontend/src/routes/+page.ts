@@ -1,10 +1,6 @@
-import { GraphQLClient } from 'graphql-request';
+import { client } from '$lib/graphql';
 import type { PageLoad } from './$types';
 
-// --- Configuration ---
-const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
-const client = new GraphQLClient(GQL_ENDPOINT);
-
 // --- The GraphQL Query ---
 const HEALTH_QUERY = `
   query HealthCheck {
@@ -1,16 +1,21 @@
-import { GraphQLClient } from 'graphql-request';
+import { fetchAllPages, type Connection } from '$lib/graphql';
 import type { PageLoad } from './$types';
 
-const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
-const client = new GraphQLClient(GQL_ENDPOINT);
-
 const GET_PROJECTS_QUERY = `
-  query GetProjects {
-    getProjects {
-      id
-      name
-      description
-      deadline
+  query GetProjects($after: String) {
+    getProjects(first: 100, after: $after) {
+      edges {
+        node {
+          id
+          name
+          description
+          deadline
+        }
+      }
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
     }
   }
 `;
@@ -23,15 +28,16 @@ interface Project {
 }
 
 interface GetProjectsResponse {
-  getProjects: Project[];
+  getProjects: Connection<Project>;
 }
 
 export const load: PageLoad = async () => {
   try {
-    const response = await client.request<GetProjectsResponse>(GET_PROJECTS_QUERY);
-    return {
-      projects: response.getProjects,
-    };
+    const projects = await fetchAllPages<GetProjectsResponse, Project>(
+      GET_PROJECTS_QUERY,
+      (response) => response.getProjects,
+    );
+    return { projects };
   } catch (error) {
     console.error("Error fetching projects:", error);
     return {
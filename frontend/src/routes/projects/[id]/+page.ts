@@ -1,9 +1,6 @@
-import { GraphQLClient } from 'graphql-request';
+import { client, fetchAllPages, type Connection } from '$lib/graphql';
 import type { PageLoad } from './$types';
 
-const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
-const client = new GraphQLClient(GQL_ENDPOINT);
-
 const GET_PROJECT_DETAILS_QUERY = `
   query GetProjectDetails($id: ID!) {
     getProjectById(id: $id) {
@@ -11,36 +8,67 @@ const GET_PROJECT_DETAILS_QUERY = `
       name
       description
       deadline
-      tasks {
-        id
-        title
-        status
-        jiraTicketId
-        raciAssignments {
-          role
-          user {
-            email
+    }
+  }
+`;
+
+const GET_PROJECT_TASKS_QUERY = `
+  query GetProjectTasks($id: ID!, $after: String) {
+    getProjectById(id: $id) {
+      tasks(first: 100, after: $after) {
+        edges {
+          node {
+            id
+            title
+            status
+            jiraTicketId
+            raciAssignments {
+              role
+              user {
+                email
+              }
+            }
           }
         }
+        pageInfo {
+          hasNextPage
+          endCursor
+        }
       }
     }
   }
 `;
 
 const GET_USERS_QUERY = `
-  query GetUsers {
-    getUsers {
-      id
-      email
+  query GetUsers($after: String) {
+    getUsers(first: 100, after: $after) {
+      edges {
+        node {
+          id
+          email
+        }
+      }
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
     }
   }
 `;
 
 const GET_TEMPLATES_QUERY = `
-  query GetWorkflowTemplates {
-    getWorkflowTemplates {
-      id
-      name
+  query GetWorkflowTemplates($after: String) {
+    getWorkflowTemplates(first: 100, after: $after) {
+      edges {
+        node {
+          id
+          name
+        }
+      }
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
     }
   }
 `;
@@ -63,7 +91,11 @@ interface Project {
 }
 
 interface GetProjectDetailsResponse {
-  getProjectById: Project | null;
+  getProjectById: Omit<Project, 'tasks'> | null;
+}
+
+interface GetProjectTasksResponse {
+  getProjectById: { tasks: Connection<Task> } | null;
 }
 
 interface User {
@@ -72,7 +104,7 @@ interface User {
 }
 
 interface GetUsersResponse {
-  getUsers: User[];
+  getUsers: Connection<User>;
 }
 
 interface RaciAssignment {
@@ -87,23 +119,31 @@ interface WorkflowTemplate {
   name: string;
 }
 interface GetTemplatesResponse {
-  getWorkflowTemplates: WorkflowTemplate[];
+  getWorkflowTemplates: Connection<WorkflowTemplate>;
 }
 
 // The 'params' object contains the dynamic parts of the URL, like our [id]
 export const load: PageLoad = async ({ params }) => {
   try {
     const projectVariables = { id: params.id };
-    const [projectResponse, usersResponse, templatesResponse] = await Promise.all([
+    const [projectResponse, tasks, users, templates] = await Promise.all([
       client.request<GetProjectDetailsResponse>(GET_PROJECT_DETAILS_QUERY, projectVariables),
-      client.request<GetUsersResponse>(GET_USERS_QUERY),
-      client.request<GetTemplatesResponse>(GET_TEMPLATES_QUERY)
+      fetchAllPages<GetProjectTasksResponse, Task>(
+        GET_PROJECT_TASKS_QUERY,
+        (response) => response.getProjectById?.tasks,
+        projectVariables,
+      ),
+      fetchAllPages<GetUsersResponse, User>(GET_USERS_QUERY, (response) => response.getUsers),
+      fetchAllPages<GetTemplatesResponse, WorkflowTemplate>(
+        GET_TEMPLATES_QUERY,
+        (response) => response.getWorkflowTemplates,
+      ),
     ]);
 
     return {
-      project: projectResponse.getProjectById,
-      users: usersResponse.getUsers,
-      templates: templatesResponse.getWorkflowTemplates,
+      project: projectResponse.getProjectById && { ...projectResponse.getProjectById, tasks },
+      users,
+      templates,
     };
   } catch (error) {
     console.error("Error fetching page data:", error);
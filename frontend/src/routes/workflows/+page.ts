@@ -1,15 +1,20 @@
-import { GraphQLClient } from 'graphql-request';
+import { fetchAllPages, type Connection } from '$lib/graphql';
 import type { PageLoad } from './$types';
 
-const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
-const client = new GraphQLClient(GQL_ENDPOINT);
-
 const GET_TEMPLATES_QUERY = `
-  query GetWorkflowTemplates {
-    getWorkflowTemplates {
-      id
-      name
-      description
+  query GetWorkflowTemplates($after: String) {
+    getWorkflowTemplates(first: 100, after: $after) {
+      edges {
+        node {
+          id
+          name
+          description
+        }
+      }
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
     }
   }
 `;
@@ -21,15 +26,16 @@ interface WorkflowTemplate {
 }
 
 interface GetTemplatesResponse {
-  getWorkflowTemplates: WorkflowTemplate[];
+  getWorkflowTemplates: Connection<WorkflowTemplate>;
 }
 
 export const load: PageLoad = async () => {
   try {
-    const response = await client.request<GetTemplatesResponse>(GET_TEMPLATES_QUERY);
-    return {
-      templates: response.getWorkflowTemplates,
-    };
+    const templates = await fetchAllPages<GetTemplatesResponse, WorkflowTemplate>(
+      GET_TEMPLATES_QUERY,
+      (response) => response.getWorkflowTemplates,
+    );
+    return { templates };
   } catch (error) {
     console.error("Error fetching workflow templates:", error);
     return {
@@ -1,9 +1,6 @@
-import { GraphQLClient } from 'graphql-request';
+import { client } from '$lib/graphql';
 import type { PageLoad } from './$types';
 
-const GQL_ENDPOINT = 'http://127.0.0.1:8080/graphql';
-const client = new GraphQLClient(GQL_ENDPOINT);
-
 const GET_TEMPLATE_DETAILS_QUERY = `
   query GetTemplateDetails($id: ID!) {
     getWorkflowTemplateById(id: $id) {